@@ -0,0 +1,321 @@
+use chrono::Duration;
+use rust_decimal::Decimal;
+use std::{cmp::Reverse, collections::HashMap};
+
+use crate::{
+    loadout::Loadout,
+    markup::Markup,
+    session::{Session, Stopwatch},
+    utils::{Helpers, Utils},
+};
+
+const COMPARISON_TOP_COUNT: usize = 3;
+
+pub struct LoadoutLifetime {
+    pub loadout_id: String,
+    pub loadout_name: String,
+    pub session_count: usize,
+    pub attack_count: usize,
+    pub crit_count: usize,
+    pub miss_count: usize,
+    pub damage: Decimal,
+    pub cost: Decimal,
+    pub loot_value: Decimal,
+    pub active_time: Duration,
+}
+
+impl LoadoutLifetime {
+    fn new(loadout_id: &str, loadout_name: &str) -> LoadoutLifetime {
+        LoadoutLifetime {
+            loadout_id: loadout_id.to_string(),
+            loadout_name: loadout_name.to_string(),
+            session_count: 0,
+            attack_count: 0,
+            crit_count: 0,
+            miss_count: 0,
+            damage: Decimal::ZERO,
+            cost: Decimal::ZERO,
+            loot_value: Decimal::ZERO,
+            active_time: Duration::zero(),
+        }
+    }
+
+    // Sessions tracked before segments existed count entirely toward their
+    // session loadout.
+    pub fn collect<'a>(
+        sessions: impl Iterator<Item = &'a Session>,
+        loadouts: &HashMap<String, Loadout>,
+    ) -> Vec<LoadoutLifetime> {
+        let mut lifetime_map: HashMap<String, LoadoutLifetime> = HashMap::new();
+        for session in sessions {
+            let mut used_loadouts: Vec<&str> = vec![];
+            if session.loadout_segments.is_empty() {
+                if session.stats.self_attack_count == 0 && session.stats.combat_cost().is_zero() {
+                    continue;
+                }
+                let loadout_id = Loadout::lookup(loadouts, &session.loadout)
+                    .map(|loadout| loadout.id.clone())
+                    .unwrap_or(match session.loadout.id.is_empty() {
+                        true => session.loadout.name.clone(),
+                        false => session.loadout.id.clone(),
+                    });
+                let lifetime = lifetime_map
+                    .entry(loadout_id.clone())
+                    .or_insert_with(|| LoadoutLifetime::new(&loadout_id, &session.loadout.name));
+                lifetime.session_count += 1;
+                lifetime.attack_count += session.stats.self_attack_count;
+                lifetime.crit_count += session.stats.self_crit_count;
+                lifetime.miss_count += session.stats.self_attack_miss_count;
+                lifetime.damage += session.stats.self_total_damage;
+                lifetime.cost += session.stats.combat_cost();
+                lifetime.loot_value += session.stats.combat_loot();
+                lifetime.active_time = lifetime.active_time + session.elapsed_time;
+                continue;
+            }
+
+            for segment in session.loadout_segments.iter() {
+                let lifetime = lifetime_map
+                    .entry(segment.loadout_id.clone())
+                    .or_insert_with(|| {
+                        LoadoutLifetime::new(&segment.loadout_id, &segment.loadout_name)
+                    });
+                if !used_loadouts.contains(&segment.loadout_id.as_str()) {
+                    used_loadouts.push(&segment.loadout_id);
+                    lifetime.session_count += 1;
+                }
+                lifetime.attack_count += segment.attack_count;
+                lifetime.crit_count += segment.crit_count;
+                lifetime.miss_count += segment.miss_count;
+                lifetime.damage += segment.damage;
+                lifetime.cost += segment.cost;
+                lifetime.loot_value += segment.loot_value;
+                lifetime.active_time = lifetime.active_time + segment.active_time;
+            }
+        }
+
+        let mut lifetimes: Vec<LoadoutLifetime> = lifetime_map
+            .into_values()
+            .map(|mut lifetime| {
+                if let Some(loadout) = loadouts.get(&lifetime.loadout_id) {
+                    lifetime.loadout_name = loadout.name.clone();
+                }
+                lifetime
+            })
+            .collect();
+        lifetimes.sort_by_key(|lifetime| Reverse(lifetime.damage_per_pec()));
+
+        lifetimes
+    }
+
+    pub fn damage_per_pec(&self) -> Decimal {
+        Utils::get_damage_per_pec(self.damage, self.cost)
+    }
+
+    pub fn crit_rate(&self) -> Decimal {
+        Utils::get_percentage(
+            Decimal::from(self.crit_count),
+            Decimal::from(self.attack_count),
+        )
+    }
+
+    pub fn miss_rate(&self) -> Decimal {
+        Utils::get_percentage(
+            Decimal::from(self.miss_count),
+            Decimal::from(self.attack_count),
+        )
+    }
+
+    pub fn cost_per_hour(&self) -> Decimal {
+        (self.cost * Decimal::from(3600))
+            .checked_div(Decimal::from(self.active_time.num_seconds()))
+            .unwrap_or(Decimal::ZERO)
+            .trunc_with_scale(4)
+    }
+
+    pub fn return_percentage(&self) -> Decimal {
+        Utils::get_percentage(self.loot_value, self.cost)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Preference {
+    Higher,
+    Lower,
+    Neutral,
+}
+
+pub struct ComparisonRow {
+    pub label: String,
+    pub values: Vec<Decimal>,
+    pub preference: Preference,
+}
+
+impl ComparisonRow {
+    fn new<'a>(
+        label: &str,
+        preference: Preference,
+        sessions: &[&'a Session],
+        value: impl Fn(&'a Session) -> Decimal,
+    ) -> ComparisonRow {
+        ComparisonRow {
+            label: label.to_string(),
+            values: sessions.iter().map(|session| value(session)).collect(),
+            preference,
+        }
+    }
+
+    pub fn delta(&self, idx: usize) -> Decimal {
+        self.values[idx] - self.values[0]
+    }
+
+    pub fn is_improvement(&self, idx: usize) -> Option<bool> {
+        let delta = self.delta(idx);
+        if delta.is_zero() {
+            return None;
+        }
+        match self.preference {
+            Preference::Higher => Some(delta.is_sign_positive()),
+            Preference::Lower => Some(delta.is_sign_negative()),
+            Preference::Neutral => None,
+        }
+    }
+}
+
+pub struct SessionComparison {
+    pub session_names: Vec<String>,
+    pub rows: Vec<ComparisonRow>,
+}
+
+impl SessionComparison {
+    pub fn collect(sessions: &[&Session], markups: &HashMap<String, Markup>) -> SessionComparison {
+        let per_hour = |value: Decimal, session: &Session| {
+            (value * Decimal::from(3600))
+                .checked_div(Decimal::from(session.elapsed().num_seconds()))
+                .unwrap_or(Decimal::ZERO)
+                .trunc_with_scale(4)
+        };
+        let mu_value = |session: &Session| {
+            session
+                .loot_map
+                .values()
+                .fold(Decimal::ZERO, |total, loot| {
+                    let markup = markups
+                        .get(&loot.name)
+                        .map(|markup| markup.value)
+                        .unwrap_or(Decimal::ONE);
+                    total + loot.tt_value * markup
+                })
+        };
+
+        let mut rows = vec![
+            ComparisonRow::new("Elapsed Minutes", Preference::Neutral, sessions, |s| {
+                Decimal::from(s.elapsed().num_minutes())
+            }),
+            ComparisonRow::new("Total Cost", Preference::Lower, sessions, |s| {
+                s.stats.total_cost.trunc_with_scale(4)
+            }),
+            ComparisonRow::new("TT Return", Preference::Higher, sessions, |s| {
+                s.stats.tt_profit.trunc_with_scale(4)
+            }),
+            ComparisonRow::new("Return %", Preference::Higher, sessions, |s| {
+                Utils::get_percentage(s.stats.tt_profit, s.stats.total_cost)
+            }),
+            ComparisonRow::new("MU Return %", Preference::Higher, sessions, |s| {
+                Utils::get_percentage(mu_value(s), s.stats.total_cost)
+            }),
+            ComparisonRow::new("PED/Hour", Preference::Higher, sessions, |s| {
+                per_hour(mu_value(s), s)
+            }),
+            ComparisonRow::new("Cost/Hour", Preference::Neutral, sessions, |s| {
+                per_hour(s.stats.total_cost, s)
+            }),
+            ComparisonRow::new("Damage", Preference::Higher, sessions, |s| {
+                s.stats.self_total_damage.trunc_with_scale(2)
+            }),
+            ComparisonRow::new("DPP", Preference::Higher, sessions, |s| {
+                s.stats.damage_per_pec()
+            }),
+            ComparisonRow::new("Shots", Preference::Neutral, sessions, |s| {
+                Decimal::from(s.stats.self_attack_count)
+            }),
+            ComparisonRow::new("Crit %", Preference::Higher, sessions, |s| {
+                Utils::get_percentage(
+                    Decimal::from(s.stats.self_crit_count),
+                    Decimal::from(s.stats.self_attack_count),
+                )
+            }),
+            ComparisonRow::new("Miss %", Preference::Lower, sessions, |s| {
+                Utils::get_percentage(
+                    Decimal::from(s.stats.self_attack_miss_count),
+                    Decimal::from(s.stats.self_attack_count),
+                )
+            }),
+            ComparisonRow::new("Deaths", Preference::Lower, sessions, |s| {
+                Decimal::from(s.stats.self_death_count)
+            }),
+            ComparisonRow::new("Globals", Preference::Higher, sessions, |s| {
+                Decimal::from(s.stats.global_count)
+            }),
+            ComparisonRow::new("HoFs", Preference::Higher, sessions, |s| {
+                Decimal::from(s.stats.hof_count)
+            }),
+            ComparisonRow::new("Exp Gain", Preference::Higher, sessions, |s| {
+                s.stats.self_total_exp_gain
+            }),
+        ];
+
+        let mut loot_names: Vec<&String> = vec![];
+        let mut skill_names: Vec<&String> = vec![];
+        for session in sessions.iter() {
+            let mut loot_vec: Vec<_> = session.loot_map.values().collect();
+            loot_vec.sort_by_key(|loot| Reverse(loot.tt_value));
+            for loot in loot_vec.into_iter().take(COMPARISON_TOP_COUNT) {
+                if !loot_names.contains(&&loot.name) {
+                    loot_names.push(&loot.name);
+                }
+            }
+
+            let mut skill_vec: Vec<_> = session.skill_map.values().collect();
+            skill_vec.sort_by_key(|skill| Reverse(skill.exp_gain));
+            for skill in skill_vec.into_iter().take(COMPARISON_TOP_COUNT) {
+                if !skill_names.contains(&&skill.name) {
+                    skill_names.push(&skill.name);
+                }
+            }
+        }
+        for name in loot_names {
+            rows.push(ComparisonRow::new(
+                &format!("Loot: {}", name),
+                Preference::Higher,
+                sessions,
+                |s| {
+                    s.loot_map
+                        .get(name)
+                        .map(|loot| loot.tt_value.trunc_with_scale(4))
+                        .unwrap_or(Decimal::ZERO)
+                },
+            ));
+        }
+        for name in skill_names {
+            rows.push(ComparisonRow::new(
+                &format!("Skill: {}", name),
+                Preference::Higher,
+                sessions,
+                |s| {
+                    s.skill_map
+                        .get(name)
+                        .map(|skill| skill.exp_gain)
+                        .unwrap_or(Decimal::ZERO)
+                },
+            ));
+        }
+
+        SessionComparison {
+            session_names: sessions
+                .iter()
+                .map(|session| session.name.clone())
+                .collect(),
+            rows,
+        }
+    }
+}
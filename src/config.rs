@@ -0,0 +1,329 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    env,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use humantime_serde::re::humantime;
+
+use crate::{
+    headless::{Headless, HeadlessOutput, DEFAULT_SUMMARY_INTERVAL},
+    replay::{parse_datetime, Replay, ReplayTarget},
+};
+
+const APP_DIR: &str = "eutracker";
+const CONFIG_FILE: &str = "config.json";
+const DEFAULT_TICK_RATE: Duration = Duration::from_millis(250);
+
+pub const USAGE: &str = "Usage: eutracker-rs [OPTIONS]
+       eutracker-rs replay --from <TIME> --to <TIME> [--session <NAME> | --new-session] [OPTIONS]
+       eutracker-rs recompute [--session <NAME>] [OPTIONS]
+
+Commands:
+  replay                    Parse a past range of chat.log into a session, the most
+                            recent session unless --session or --new-session is given
+                            TIME is YYYY-MM-DD HH:MM[:SS] in local time
+  recompute                 Rebuild a session's stats from its event journal using the
+                            current loadout values, the most recent session by default
+
+Options:
+  -c, --config <FILE>       Config file (default: <config dir>/eutracker/config.json)
+  -l, --log-path <FILE>     Path to the Entropia Universe chat.log
+  -a, --avatar <NAME>       Full avatar name used to credit globals
+  -d, --data-dir <DIR>      Directory holding sessions, loadouts and markups
+                            (default: <data dir>/eutracker)
+  -t, --tick-rate <TIME>    UI refresh and save interval, e.g. 250ms or 1s
+      --headless            Track without the terminal UI, printing to stdout
+      --output <FORMAT>     Headless output, summary (default) or events (JSON lines)
+      --summary-interval <TIME>
+                            Time between headless summaries (default: 60s)
+  -h, --help                Print this help";
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ConfigFile {
+    pub log_path: Option<PathBuf>,
+    pub avatar_name: Option<String>,
+    pub data_dir: Option<PathBuf>,
+    #[serde(with = "humantime_serde")]
+    pub tick_rate: Option<Duration>,
+}
+
+impl ConfigFile {
+    pub fn load(path: &Path) -> Result<ConfigFile, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("could not read config file {}: {}", path.display(), e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("invalid config file {}: {}", path.display(), e))
+    }
+
+    fn merge(self, other: ConfigFile) -> ConfigFile {
+        ConfigFile {
+            log_path: other.log_path.or(self.log_path),
+            avatar_name: other.avatar_name.or(self.avatar_name),
+            data_dir: other.data_dir.or(self.data_dir),
+            tick_rate: other.tick_rate.or(self.tick_rate),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Args {
+    pub help: bool,
+    pub config_path: Option<PathBuf>,
+    pub overrides: ConfigFile,
+    pub command: Command,
+    pub headless: Option<Headless>,
+}
+
+#[derive(Default)]
+pub enum Command {
+    #[default]
+    Track,
+    Replay(Replay),
+    Recompute(Option<String>),
+}
+
+#[derive(Default)]
+struct HeadlessArgs {
+    requested: bool,
+    output: Option<HeadlessOutput>,
+    summary_interval: Option<Duration>,
+}
+
+impl HeadlessArgs {
+    fn into_headless(self) -> Result<Option<Headless>, String> {
+        if !self.requested {
+            if self.output.is_some() || self.summary_interval.is_some() {
+                return Err(String::from(
+                    "--output and --summary-interval are only valid with --headless",
+                ));
+            }
+            return Ok(None);
+        }
+
+        let summary_interval = self.summary_interval.unwrap_or(DEFAULT_SUMMARY_INTERVAL);
+        if summary_interval.is_zero() {
+            return Err(String::from("summary interval must be greater than zero"));
+        }
+
+        Ok(Some(Headless {
+            output: self.output.unwrap_or(HeadlessOutput::Summary),
+            summary_interval,
+        }))
+    }
+}
+
+#[derive(Default)]
+struct CommandArgs {
+    name: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    session: Option<String>,
+    new_session: bool,
+}
+
+impl CommandArgs {
+    fn into_command(self) -> Result<Command, String> {
+        match self.name.as_deref() {
+            None => {
+                if self.from.is_some()
+                    || self.to.is_some()
+                    || self.session.is_some()
+                    || self.new_session
+                {
+                    return Err(String::from(
+                        "--from, --to, --session and --new-session are only valid with replay or recompute",
+                    ));
+                }
+                Ok(Command::Track)
+            }
+            Some("replay") => {
+                let from = parse_datetime(&self.from.ok_or("replay requires --from")?)?;
+                let to = parse_datetime(&self.to.ok_or("replay requires --to")?)?;
+                let target = match (self.session, self.new_session) {
+                    (Some(_), true) => {
+                        return Err(String::from(
+                            "--session and --new-session cannot be used together",
+                        ))
+                    }
+                    (Some(name), false) => ReplayTarget::Existing(name),
+                    (None, true) => ReplayTarget::New,
+                    (None, false) => ReplayTarget::Current,
+                };
+
+                Replay::new(from, to, target).map(Command::Replay)
+            }
+            Some(_) => {
+                if self.from.is_some() || self.to.is_some() || self.new_session {
+                    return Err(String::from(
+                        "--from, --to and --new-session are only valid with replay",
+                    ));
+                }
+                Ok(Command::Recompute(self.session))
+            }
+        }
+    }
+}
+
+impl Args {
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Args, String> {
+        let mut parsed = Args::default();
+        let mut command = CommandArgs::default();
+        let mut headless = HeadlessArgs::default();
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => {
+                    (flag.to_string(), Some(value.to_string()))
+                }
+                _ => (arg, None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or(format!("missing value for {}", flag))
+            };
+            match flag.as_str() {
+                "-h" | "--help" => parsed.help = true,
+                "-c" | "--config" => parsed.config_path = Some(PathBuf::from(value()?)),
+                "-l" | "--log-path" => parsed.overrides.log_path = Some(PathBuf::from(value()?)),
+                "-a" | "--avatar" => parsed.overrides.avatar_name = Some(value()?),
+                "-d" | "--data-dir" => parsed.overrides.data_dir = Some(PathBuf::from(value()?)),
+                "-t" | "--tick-rate" => {
+                    parsed.overrides.tick_rate = Some(parse_duration("tick rate", &value()?)?)
+                }
+                "--headless" => headless.requested = true,
+                "--output" => headless.output = Some(HeadlessOutput::from_name(&value()?)?),
+                "--summary-interval" => {
+                    headless.summary_interval = Some(parse_duration("summary interval", &value()?)?)
+                }
+                "replay" | "recompute" if command.name.is_none() => command.name = Some(flag),
+                "--from" => command.from = Some(value()?),
+                "--to" => command.to = Some(value()?),
+                "--session" => command.session = Some(value()?),
+                "--new-session" => command.new_session = true,
+                _ => return Err(format!("unknown argument '{}'", flag)),
+            }
+        }
+        if !parsed.help {
+            parsed.command = command.into_command()?;
+            parsed.headless = headless.into_headless()?;
+            if !matches!(parsed.command, Command::Track) && parsed.headless.is_some() {
+                return Err(String::from(
+                    "replay and recompute cannot be combined with --headless",
+                ));
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+pub struct Config {
+    pub log_path: PathBuf,
+    pub avatar_name: String,
+    pub data_dir: PathBuf,
+    pub tick_rate: Duration,
+}
+
+impl Config {
+    pub fn load(args: &mut Args) -> Result<Config, String> {
+        let file = match &args.config_path {
+            Some(path) => ConfigFile::load(path)?,
+            None => match Config::default_path() {
+                Some(path) if path.exists() => ConfigFile::load(&path)?,
+                _ => ConfigFile::default(),
+            },
+        };
+
+        Config::validate(file.merge(std::mem::take(&mut args.overrides)))
+    }
+
+    pub fn default_path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(APP_DIR).join(CONFIG_FILE))
+    }
+
+    fn validate(file: ConfigFile) -> Result<Config, String> {
+        let log_path = file.log_path.or_else(default_log_path).ok_or(
+            "no chat.log path configured, pass --log-path or set log_path in the config file",
+        )?;
+        if !log_path.is_file() {
+            return Err(format!("chat.log not found at {}", log_path.display()));
+        }
+        let log_path = std::path::absolute(&log_path)
+            .map_err(|e| format!("could not resolve {}: {}", log_path.display(), e))?;
+
+        let avatar_name = file.avatar_name.unwrap_or_default().trim().to_string();
+        if avatar_name.is_empty() {
+            return Err(String::from(
+                "no avatar name configured, pass --avatar or set avatar_name in the config file",
+            ));
+        }
+
+        let data_dir = file
+            .data_dir
+            .or_else(|| data_dir().map(|dir| dir.join(APP_DIR)))
+            .ok_or("no data directory found, pass --data-dir or set data_dir in the config file")?;
+        std::fs::create_dir_all(&data_dir).map_err(|e| {
+            format!(
+                "could not create data directory {}: {}",
+                data_dir.display(),
+                e
+            )
+        })?;
+        let data_dir = std::path::absolute(&data_dir)
+            .map_err(|e| format!("could not resolve {}: {}", data_dir.display(), e))?;
+
+        let tick_rate = file.tick_rate.unwrap_or(DEFAULT_TICK_RATE);
+        if tick_rate.is_zero() {
+            return Err(String::from("tick rate must be greater than zero"));
+        }
+
+        Ok(Config {
+            log_path,
+            avatar_name,
+            data_dir,
+            tick_rate,
+        })
+    }
+}
+
+fn parse_duration(name: &str, raw: &str) -> Result<Duration, String> {
+    humantime::parse_duration(raw).map_err(|e| format!("invalid {} '{}': {}", name, raw, e))
+}
+
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+fn config_dir() -> Option<PathBuf> {
+    if cfg!(windows) {
+        return env::var_os("APPDATA").map(PathBuf::from);
+    }
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".config")))
+}
+
+fn data_dir() -> Option<PathBuf> {
+    if cfg!(windows) {
+        return env::var_os("APPDATA").map(PathBuf::from);
+    }
+    env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".local").join("share")))
+}
+
+fn default_log_path() -> Option<PathBuf> {
+    home_dir()
+        .map(|home| {
+            home.join("Documents")
+                .join("Entropia Universe")
+                .join("chat.log")
+        })
+        .filter(|path| path.is_file())
+}
@@ -0,0 +1,131 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::{
+    logger::EventType,
+    markup::Markup,
+    utils::{Helpers, Utils},
+};
+
+pub const UNKNOWN_BLUEPRINT: &str = "Unknown";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BlueprintInput {
+    pub name: String,
+    pub tt_value: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Blueprint {
+    pub name: String,
+    pub inputs: Vec<BlueprintInput>,
+}
+
+impl Blueprint {
+    // Expects e.g. `Explosive Projectiles; Blazar Fragment=0.01, Lysterium Ingot=0.05`
+    // with the TT value of each material used per click
+    pub fn parse(input: &str) -> Result<Blueprint, String> {
+        let (name, inputs) = input.split_once(';').unwrap_or((input, ""));
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(String::from("blueprint name is empty"));
+        }
+
+        let mut blueprint_inputs = vec![];
+        for material in inputs.split(',').filter(|m| !m.trim().is_empty()) {
+            let (material_name, tt_value) = material
+                .split_once('=')
+                .ok_or(format!("expected MATERIAL=TT, got '{}'", material.trim()))?;
+            let tt_value = Decimal::from_str_exact(tt_value.trim())
+                .map_err(|_| format!("invalid TT value '{}'", tt_value.trim()))?;
+            blueprint_inputs.push(BlueprintInput {
+                name: material_name.trim().to_string(),
+                tt_value,
+            });
+        }
+
+        Ok(Blueprint {
+            name: name.to_string(),
+            inputs: blueprint_inputs,
+        })
+    }
+
+    pub fn click_tt_cost(&self) -> Decimal {
+        self.inputs.iter().map(|input| input.tt_value).sum()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BlueprintStats {
+    pub name: String,
+    pub attempt_count: usize,
+    pub success_count: usize,
+    pub near_success_count: usize,
+    pub fail_count: usize,
+    pub input_map: HashMap<String, Decimal>,
+    pub output_map: HashMap<String, Decimal>,
+    pub residue_value: Decimal,
+}
+
+impl BlueprintStats {
+    pub fn new(name: &str) -> BlueprintStats {
+        BlueprintStats {
+            name: name.to_string(),
+            attempt_count: 0,
+            success_count: 0,
+            near_success_count: 0,
+            fail_count: 0,
+            input_map: HashMap::new(),
+            output_map: HashMap::new(),
+            residue_value: Decimal::ZERO,
+        }
+    }
+
+    pub fn add_attempt(&mut self, event_type: EventType, inputs: &[BlueprintInput]) {
+        self.attempt_count += 1;
+        match event_type {
+            EventType::CraftSuccess => self.success_count += 1,
+            EventType::CraftNearSuccess => self.near_success_count += 1,
+            _ => self.fail_count += 1,
+        }
+        for input in inputs {
+            *self.input_map.entry(input.name.clone()).or_default() += input.tt_value;
+        }
+    }
+
+    pub fn add_output(&mut self, item: &str, value: Decimal) {
+        *self.output_map.entry(item.to_string()).or_default() += value;
+        if item.contains("Residue") {
+            self.residue_value += value;
+        }
+    }
+
+    pub fn success_rate(&self) -> Decimal {
+        Utils::get_percentage(
+            Decimal::from(self.success_count),
+            Decimal::from(self.attempt_count),
+        )
+    }
+
+    pub fn input_cost(&self, markups: &HashMap<String, Markup>) -> Decimal {
+        with_markup(&self.input_map, markups)
+    }
+
+    pub fn output_value(&self, markups: &HashMap<String, Markup>) -> Decimal {
+        with_markup(&self.output_map, markups)
+    }
+}
+
+fn with_markup(values: &HashMap<String, Decimal>, markups: &HashMap<String, Markup>) -> Decimal {
+    values
+        .iter()
+        .map(|(name, tt_value)| {
+            tt_value
+                * markups
+                    .get(name)
+                    .map(|markup| markup.value)
+                    .unwrap_or(Decimal::ONE)
+        })
+        .sum()
+}
@@ -0,0 +1,149 @@
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet},
+    fs::{File, OpenOptions},
+    hash::{Hash, Hasher},
+    io::{BufRead, BufReader, Write},
+};
+
+use crate::{
+    logger::{EventType, Log},
+    storage::Storage,
+};
+
+#[derive(Serialize, Deserialize, Hash, Eq, PartialEq, Clone, Copy)]
+pub enum GlobalCategory {
+    Hunting,
+    Mining,
+    Crafting,
+    Item,
+}
+
+impl GlobalCategory {
+    pub const ALL: [GlobalCategory; 4] = [
+        GlobalCategory::Hunting,
+        GlobalCategory::Mining,
+        GlobalCategory::Crafting,
+        GlobalCategory::Item,
+    ];
+
+    pub fn from_event_type(event_type: EventType) -> Option<(GlobalCategory, bool)> {
+        match event_type {
+            EventType::GlobalHuntHOF => Some((GlobalCategory::Hunting, true)),
+            EventType::GlobalHunt => Some((GlobalCategory::Hunting, false)),
+            EventType::GlobalMiningHOF => Some((GlobalCategory::Mining, true)),
+            EventType::GlobalMining => Some((GlobalCategory::Mining, false)),
+            EventType::GlobalCraftingHOF => Some((GlobalCategory::Crafting, true)),
+            EventType::GlobalCrafting => Some((GlobalCategory::Crafting, false)),
+            EventType::GlobalItemHOF => Some((GlobalCategory::Item, true)),
+            EventType::GlobalItem => Some((GlobalCategory::Item, false)),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GlobalCategory::Hunting => "Hunting",
+            GlobalCategory::Mining => "Mining",
+            GlobalCategory::Crafting => "Crafting",
+            GlobalCategory::Item => "Item",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Hash)]
+pub struct GlobalRecord {
+    pub timestamp: NaiveDateTime,
+    pub category: GlobalCategory,
+    pub player: String,
+    pub target: String,
+    pub value: Decimal,
+    pub hof: bool,
+}
+
+impl GlobalRecord {
+    pub fn from_log(log: &Log) -> Option<GlobalRecord> {
+        let (category, hof) = GlobalCategory::from_event_type(log.event_type)?;
+
+        Some(GlobalRecord {
+            timestamp: log.timestamp,
+            category,
+            player: log.values.first()?.to_string(),
+            target: log.values.get(1)?.to_string(),
+            value: Decimal::from_str_exact(log.values.get(2)?).ok()?,
+            hof,
+        })
+    }
+
+    // The filter has to be lowercase already
+    pub fn matches(&self, lowercase_filter: &str) -> bool {
+        self.player.to_lowercase().contains(lowercase_filter)
+            || self.target.to_lowercase().contains(lowercase_filter)
+            || self
+                .category
+                .name()
+                .to_lowercase()
+                .contains(lowercase_filter)
+    }
+
+    fn key(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+pub struct GlobalHistory {
+    records: Vec<GlobalRecord>,
+    keys: HashSet<u64>,
+}
+
+impl GlobalHistory {
+    pub fn load(storage: &Storage) -> GlobalHistory {
+        let mut history = GlobalHistory {
+            records: vec![],
+            keys: HashSet::new(),
+        };
+        let file = match File::open(storage.globals_file()) {
+            Ok(file) => file,
+            Err(_) => return history,
+        };
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            if let Ok(record) = serde_json::from_str::<GlobalRecord>(&line) {
+                if history.keys.insert(record.key()) {
+                    history.records.push(record);
+                }
+            }
+        }
+        history.records.sort_by_key(|record| record.timestamp);
+
+        history
+    }
+
+    pub fn records(&self) -> &[GlobalRecord] {
+        &self.records
+    }
+
+    pub fn insert(&mut self, storage: &Storage, record: GlobalRecord) -> bool {
+        if !self.keys.insert(record.key()) {
+            return false;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(storage.globals_file())
+            .unwrap();
+        let mut contents = serde_json::to_string(&record).unwrap();
+        contents.push('\n');
+        file.write_all(contents.as_bytes()).unwrap();
+
+        let idx = self
+            .records
+            .partition_point(|recorded| recorded.timestamp <= record.timestamp);
+        self.records.insert(idx, record);
+
+        true
+    }
+}
@@ -0,0 +1,135 @@
+use chrono::Local;
+use serde_json::json;
+use std::{
+    path::Path,
+    sync::mpsc::{Receiver, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    logger::Log,
+    markup::Markup,
+    parser::{Base as ParserBase, Parser, TIMESTAMP_FORMAT},
+    session::Stopwatch,
+    tracker::{Base as TrackerBase, Tracker},
+    utils::{Helpers, Utils},
+};
+
+pub const DEFAULT_SUMMARY_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum HeadlessOutput {
+    Summary,
+    Events,
+}
+
+impl HeadlessOutput {
+    pub fn from_name(name: &str) -> Result<HeadlessOutput, String> {
+        match name {
+            "summary" => Ok(HeadlessOutput::Summary),
+            "events" => Ok(HeadlessOutput::Events),
+            _ => Err(format!(
+                "invalid output '{}', expected summary or events",
+                name
+            )),
+        }
+    }
+}
+
+pub struct Headless {
+    pub output: HeadlessOutput,
+    pub summary_interval: Duration,
+}
+
+pub fn run(
+    tracker: &mut Tracker,
+    mut parser: Parser,
+    parser_receiver: Receiver<notify::Result<notify::Event>>,
+    log_path: &Path,
+    tick_rate: Duration,
+    headless: Headless,
+) {
+    if !tracker.current_session.is_active {
+        tracker.current_session.start();
+    }
+    println!(
+        "Tracking {} into {}",
+        log_path.display(),
+        tracker.current_session.name
+    );
+
+    let mut last_tick = Instant::now();
+    let mut last_summary = Instant::now();
+    loop {
+        let timeout = tick_rate
+            .checked_sub(last_tick.elapsed())
+            .unwrap_or(Duration::ZERO);
+        match parser_receiver.recv_timeout(timeout) {
+            Ok(_) => {
+                if let Some(lines) = parser.get_lines_to_parse(log_path) {
+                    for line in lines {
+                        if let Some(log) = parser.parse(&line) {
+                            if headless.output == HeadlessOutput::Events {
+                                print_event(&log);
+                            }
+                            tracker.track(log);
+                        }
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if last_tick.elapsed() >= tick_rate {
+            save(tracker);
+            last_tick = Instant::now();
+        }
+        if headless.output == HeadlessOutput::Summary
+            && last_summary.elapsed() >= headless.summary_interval
+        {
+            print_summary(tracker);
+            last_summary = Instant::now();
+        }
+    }
+
+    save(tracker);
+}
+
+// A paused copy keeps the elapsed time on disk correct when the process
+// is killed between ticks
+fn save(tracker: &Tracker) {
+    let mut session = tracker.current_session.clone();
+    if session.is_active {
+        session.pause();
+    }
+    session.save(&tracker.storage);
+    session.loadout.save(&tracker.storage);
+    Markup::save(&tracker.storage, tracker.markups.clone());
+}
+
+fn print_event(log: &Log) {
+    let event = json!({
+        "timestamp": log.timestamp.format(TIMESTAMP_FORMAT).to_string(),
+        "channel": log.channel,
+        "event_type": log.event_type,
+        "values": log.values,
+    });
+    println!("{}", event);
+}
+
+fn print_summary(tracker: &Tracker) {
+    let session = &tracker.current_session;
+    println!(
+        "[{}] {} | {} | shots {} | damage {} | cost {} PED | TT return {} PED ({}%) | globals {}",
+        Local::now().format("%H:%M:%S"),
+        session.name,
+        session.pretty_elapsed(),
+        session.stats.self_attack_count,
+        session.stats.self_total_damage,
+        session.stats.total_cost.trunc_with_scale(4),
+        session.stats.tt_profit.trunc_with_scale(4),
+        Utils::get_percentage(session.stats.tt_profit, session.stats.total_cost),
+        session.stats.global_count,
+    );
+}
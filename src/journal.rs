@@ -0,0 +1,80 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+};
+
+use crate::{
+    crafting::UNKNOWN_BLUEPRINT,
+    logger::{EventType, Log},
+    session::{Activity, Session},
+    storage::Storage,
+};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SessionEvent {
+    pub timestamp: NaiveDateTime,
+    pub event_type: EventType,
+    pub values: Vec<String>,
+    pub activity: Activity,
+    pub loadout: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blueprint: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub resumed: bool,
+}
+
+impl SessionEvent {
+    pub fn from_log(log: &Log, session: &Session) -> SessionEvent {
+        let blueprint = match session.activity {
+            Activity::Crafting => Some(
+                session
+                    .active_blueprint
+                    .clone()
+                    .unwrap_or(String::from(UNKNOWN_BLUEPRINT)),
+            ),
+            _ => None,
+        };
+        SessionEvent {
+            timestamp: log.timestamp,
+            event_type: log.event_type,
+            values: log.values.clone(),
+            activity: session.activity,
+            loadout: session.loadout.id.clone(),
+            blueprint,
+            resumed: session.resumed,
+        }
+    }
+}
+
+pub struct Journal {}
+
+impl Journal {
+    pub fn append(storage: &Storage, session_name: &str, event: &SessionEvent) {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(storage.journal_file(session_name))
+            .unwrap();
+        let mut contents = serde_json::to_string(event).unwrap();
+        contents.push('\n');
+        file.write_all(contents.as_bytes()).unwrap();
+    }
+
+    // Replayed events are appended late but sort back into place
+    pub fn load(storage: &Storage, session_name: &str) -> Vec<SessionEvent> {
+        let file = match File::open(storage.journal_file(session_name)) {
+            Ok(file) => file,
+            Err(_) => return vec![],
+        };
+        let mut events: Vec<SessionEvent> = BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect();
+        events.sort_by_key(|event| event.timestamp);
+
+        events
+    }
+}
@@ -1,33 +1,104 @@
 use chrono::serde::ts_seconds;
 use chrono::{DateTime, Utc};
-use glob::glob;
 use std::{collections::HashMap, fs::File, io::Write, path::Path};
 
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
+use crate::storage::Storage;
+
+const DAMAGE_ENHANCER_INCREASE: Decimal = Decimal::from_parts(10, 0, 0, false, 2);
+const ECONOMY_ENHANCER_DECREASE: Decimal = Decimal::from_parts(11, 0, 0, false, 3);
+const MAX_ENHANCERS: u8 = 10;
+const AMMO_PER_PEC: Decimal = Decimal::from_parts(100, 0, 0, false, 0);
+const DEFAULT_LOADOUT_ID: &str = "default_loadout";
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Loadout {
+    // Files saved before IDs existed use their file stem
+    #[serde(default)]
+    pub id: String,
     pub name: String,
     pub weapon: Option<String>,
     pub amp: Option<String>,
     pub scope: Option<String>,
     pub sight_one: Option<String>,
     pub sight_two: Option<String>,
+    // Decays are in PEC, ammo burn in units of 0.01 PEC
     pub decay: Decimal,
     pub burn: usize,
+    #[serde(default)]
+    pub amp_decay: Decimal,
+    #[serde(default)]
+    pub amp_burn: usize,
+    #[serde(default)]
+    pub scope_decay: Decimal,
+    #[serde(default)]
+    pub sight_one_decay: Decimal,
+    #[serde(default)]
+    pub sight_two_decay: Decimal,
+    #[serde(default)]
+    pub damage_enhancers: u8,
+    #[serde(default)]
+    pub economy_enhancers: u8,
+    pub finder: Option<String>,
+    pub finder_amp: Option<String>,
+    pub excavator: Option<String>,
+    #[serde(default)]
+    pub finder_decay: Decimal,
+    #[serde(default)]
+    pub finder_amp_decay: Decimal,
+    #[serde(default)]
+    pub probe_cost: Decimal,
+    #[serde(default)]
+    pub excavator_decay: Decimal,
+    pub healing_tool: Option<String>,
+    #[serde(default)]
+    pub healing_decay: Decimal,
+    #[serde(default)]
+    pub healing_burn: usize,
+    pub armor: Option<String>,
+    #[serde(default)]
+    pub armor_protection: Decimal,
+    #[serde(default)]
+    pub armor_decay: Decimal,
+    pub plates: Option<String>,
+    #[serde(default)]
+    pub plate_protection: Decimal,
+    #[serde(default)]
+    pub plate_decay: Decimal,
+    #[serde(default)]
+    pub archived: bool,
     #[serde(with = "ts_seconds")]
     pub created_at: DateTime<Utc>,
 }
 
 impl Loadout {
-    pub fn new(loadout_name: &str) -> Loadout {
-        let formatted_name = format!(
-            "{}_loadout.json",
-            loadout_name.replace(" ", "_").to_lowercase()
-        );
-        let current_loadout_file = Path::new(formatted_name.as_str());
-        let default_loadout = Loadout {
+    pub fn new(storage: &Storage, loadout_name: &str) -> Loadout {
+        let new_loadout = Loadout::empty(&Loadout::new_id(), loadout_name);
+        new_loadout.save(storage);
+
+        new_loadout
+    }
+
+    pub fn new_id() -> String {
+        format!("loadout_{}", Utc::now().timestamp_millis())
+    }
+
+    pub fn default_loadout(storage: &Storage) -> Loadout {
+        let default_loadout_file = storage.loadout_file(&Loadout::file_name(DEFAULT_LOADOUT_ID));
+        if let Some(loadout) = Loadout::load(&default_loadout_file) {
+            return loadout;
+        }
+        let default_loadout = Loadout::empty(DEFAULT_LOADOUT_ID, "default");
+        default_loadout.save(storage);
+
+        default_loadout
+    }
+
+    fn empty(id: &str, loadout_name: &str) -> Loadout {
+        Loadout {
+            id: String::from(id),
             name: String::from(loadout_name),
             weapon: None,
             amp: None,
@@ -36,14 +107,70 @@ impl Loadout {
             sight_two: None,
             decay: Decimal::new(0, 6),
             burn: 0,
+            amp_decay: Decimal::new(0, 6),
+            amp_burn: 0,
+            scope_decay: Decimal::new(0, 6),
+            sight_one_decay: Decimal::new(0, 6),
+            sight_two_decay: Decimal::new(0, 6),
+            damage_enhancers: 0,
+            economy_enhancers: 0,
+            finder: None,
+            finder_amp: None,
+            excavator: None,
+            finder_decay: Decimal::new(0, 6),
+            finder_amp_decay: Decimal::new(0, 6),
+            probe_cost: Decimal::new(0, 6),
+            excavator_decay: Decimal::new(0, 6),
+            healing_tool: None,
+            healing_decay: Decimal::new(0, 6),
+            healing_burn: 0,
+            armor: None,
+            armor_protection: Decimal::new(0, 6),
+            armor_decay: Decimal::new(0, 6),
+            plates: None,
+            plate_protection: Decimal::new(0, 6),
+            plate_decay: Decimal::new(0, 6),
+            archived: false,
             created_at: Utc::now(),
-        };
+        }
+    }
 
-        let mut file = File::create(current_loadout_file).unwrap();
-        let contents = serde_json::to_string_pretty(&default_loadout).unwrap();
-        file.write_all(contents.as_bytes()).unwrap();
+    pub fn weapon_multiplier(&self) -> Decimal {
+        (Decimal::ONE + DAMAGE_ENHANCER_INCREASE * Decimal::from(self.damage_enhancers))
+            * (Decimal::ONE - ECONOMY_ENHANCER_DECREASE * Decimal::from(self.economy_enhancers))
+    }
 
-        default_loadout
+    pub fn cost_per_shot(&self) -> Decimal {
+        let weapon_pec =
+            (self.decay + Decimal::from(self.burn) / AMMO_PER_PEC) * self.weapon_multiplier();
+        let amp_pec = self.amp_decay + Decimal::from(self.amp_burn) / AMMO_PER_PEC;
+        let attachments_pec = self.scope_decay + self.sight_one_decay + self.sight_two_decay;
+
+        (weapon_pec + amp_pec + attachments_pec) / Decimal::ONE_HUNDRED
+    }
+
+    pub fn cost_per_drop(&self) -> Decimal {
+        self.probe_cost + self.finder_decay * Decimal::new(1, 2)
+    }
+
+    pub fn finder_amp_cost_per_drop(&self) -> Decimal {
+        self.finder_amp_decay * Decimal::new(1, 2)
+    }
+
+    pub fn cost_per_extraction(&self) -> Decimal {
+        self.excavator_decay * Decimal::new(1, 2)
+    }
+
+    pub fn cost_per_heal(&self) -> Decimal {
+        (self.healing_decay + Decimal::from(self.healing_burn) * Decimal::new(1, 2))
+            * Decimal::new(1, 2)
+    }
+
+    // The log only shows damage that got through, so plates and armor are
+    // assumed to absorb their full protection on every hit
+    pub fn armor_cost_per_hit(&self) -> Decimal {
+        (self.plate_protection * self.plate_decay + self.armor_protection * self.armor_decay)
+            * Decimal::new(1, 2)
     }
 
     pub fn load(path: &Path) -> Option<Loadout> {
@@ -51,37 +178,62 @@ impl Loadout {
             return None;
         }
         let contents = std::fs::read_to_string(path).unwrap();
-        let loadout: Loadout = serde_json::from_str(&contents).unwrap();
+        let mut loadout: Loadout = serde_json::from_str(&contents).unwrap();
+        if loadout.id.is_empty() {
+            loadout.id = path.file_stem().unwrap().to_str().unwrap().to_string();
+        }
 
         Some(loadout)
     }
 
-    pub fn fetch() -> HashMap<String, Loadout> {
+    pub fn fetch(storage: &Storage) -> HashMap<String, Loadout> {
+        let mut loadout_map = Loadout::fetch_all(storage);
+        loadout_map.retain(|_, loadout| !loadout.archived);
+
+        loadout_map
+    }
+
+    pub fn fetch_all(storage: &Storage) -> HashMap<String, Loadout> {
         let mut loadout_map: HashMap<String, Loadout> = HashMap::new();
-        for entry in glob("*_loadout.json").unwrap() {
-            if let Ok(file_path) = entry {
-                let loadout = Loadout::load(&file_path).unwrap();
-                loadout_map.insert(
-                    file_path.file_stem().unwrap().to_str().unwrap().to_string(),
-                    loadout,
-                );
-            }
+        for file_path in Storage::json_files(&storage.loadouts_dir()) {
+            let loadout = Loadout::load(&file_path).unwrap();
+            loadout_map.insert(loadout.id.clone(), loadout);
         }
 
         loadout_map
     }
 
-    pub fn save(self: &Self) {
-        let formatted_name = format!(
-            "{}_loadout.json",
-            self.name.replace(" ", "_").to_lowercase()
-        );
-        let current_loadout_file = Path::new(formatted_name.as_str());
+    // Sessions saved before loadouts had IDs only know the loadout name
+    pub fn lookup<'a>(
+        loadouts: &'a HashMap<String, Loadout>,
+        loadout: &Loadout,
+    ) -> Option<&'a Loadout> {
+        match loadout.id.is_empty() {
+            true => loadouts.values().find(|saved| saved.name == loadout.name),
+            false => loadouts.get(&loadout.id),
+        }
+    }
+
+    pub fn file_name(id: &str) -> String {
+        format!("{}.json", id)
+    }
+
+    pub fn save(&self, storage: &Storage) {
+        let current_loadout_file = storage.loadout_file(&Loadout::file_name(&self.id));
         let mut file = File::create(current_loadout_file).unwrap();
         let contents = serde_json::to_string_pretty(self).unwrap();
         file.write_all(contents.as_bytes()).unwrap();
     }
 
+    pub fn remove(storage: &Storage, id: &str) -> Result<(), String> {
+        let loadout_file = storage.loadout_file(&Loadout::file_name(id));
+        if loadout_file.exists() {
+            std::fs::remove_file(loadout_file).map_err(|e| e.to_string())?;
+        }
+
+        Ok(())
+    }
+
     pub fn export(self: &Self, loadout_name: &str) {
         let current_loadout_file = Path::new(loadout_name);
         let mut file: File = File::create(current_loadout_file).unwrap();
@@ -89,3 +241,307 @@ impl Loadout {
         file.write_all(contents.as_bytes()).unwrap();
     }
 }
+
+#[derive(Clone, Copy)]
+pub enum LoadoutField {
+    Name,
+    Weapon,
+    Decay,
+    Burn,
+    DamageEnhancers,
+    EconomyEnhancers,
+    Amp,
+    AmpDecay,
+    AmpBurn,
+    Scope,
+    ScopeDecay,
+    SightOne,
+    SightOneDecay,
+    SightTwo,
+    SightTwoDecay,
+    HealingTool,
+    HealingDecay,
+    HealingBurn,
+    Armor,
+    ArmorProtection,
+    ArmorDecay,
+    Plates,
+    PlateProtection,
+    PlateDecay,
+    Finder,
+    FinderAmp,
+    Excavator,
+    FinderDecay,
+    FinderAmpDecay,
+    ProbeCost,
+    ExcavatorDecay,
+}
+
+impl LoadoutField {
+    pub const ALL: [LoadoutField; 31] = [
+        LoadoutField::Name,
+        LoadoutField::Weapon,
+        LoadoutField::Decay,
+        LoadoutField::Burn,
+        LoadoutField::DamageEnhancers,
+        LoadoutField::EconomyEnhancers,
+        LoadoutField::Amp,
+        LoadoutField::AmpDecay,
+        LoadoutField::AmpBurn,
+        LoadoutField::Scope,
+        LoadoutField::ScopeDecay,
+        LoadoutField::SightOne,
+        LoadoutField::SightOneDecay,
+        LoadoutField::SightTwo,
+        LoadoutField::SightTwoDecay,
+        LoadoutField::HealingTool,
+        LoadoutField::HealingDecay,
+        LoadoutField::HealingBurn,
+        LoadoutField::Armor,
+        LoadoutField::ArmorProtection,
+        LoadoutField::ArmorDecay,
+        LoadoutField::Plates,
+        LoadoutField::PlateProtection,
+        LoadoutField::PlateDecay,
+        LoadoutField::Finder,
+        LoadoutField::FinderAmp,
+        LoadoutField::Excavator,
+        LoadoutField::FinderDecay,
+        LoadoutField::FinderAmpDecay,
+        LoadoutField::ProbeCost,
+        LoadoutField::ExcavatorDecay,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LoadoutField::Name => "Name",
+            LoadoutField::Weapon => "Weapon",
+            LoadoutField::Decay => "Weapon Decay (PEC)",
+            LoadoutField::Burn => "Weapon Ammo Burn",
+            LoadoutField::DamageEnhancers => "Damage Enhancers",
+            LoadoutField::EconomyEnhancers => "Economy Enhancers",
+            LoadoutField::Amp => "Amp",
+            LoadoutField::AmpDecay => "Amp Decay (PEC)",
+            LoadoutField::AmpBurn => "Amp Ammo Burn",
+            LoadoutField::Scope => "Scope",
+            LoadoutField::ScopeDecay => "Scope Decay (PEC)",
+            LoadoutField::SightOne => "Sight 1",
+            LoadoutField::SightOneDecay => "Sight 1 Decay (PEC)",
+            LoadoutField::SightTwo => "Sight 2",
+            LoadoutField::SightTwoDecay => "Sight 2 Decay (PEC)",
+            LoadoutField::HealingTool => "Healing Tool",
+            LoadoutField::HealingDecay => "Healing Decay (PEC)",
+            LoadoutField::HealingBurn => "Healing Burn",
+            LoadoutField::Armor => "Armor",
+            LoadoutField::ArmorProtection => "Armor Protection",
+            LoadoutField::ArmorDecay => "Armor Decay (PEC/dmg)",
+            LoadoutField::Plates => "Plates",
+            LoadoutField::PlateProtection => "Plate Protection",
+            LoadoutField::PlateDecay => "Plate Decay (PEC/dmg)",
+            LoadoutField::Finder => "Finder",
+            LoadoutField::FinderAmp => "Finder Amp",
+            LoadoutField::Excavator => "Excavator",
+            LoadoutField::FinderDecay => "Finder Decay (PEC)",
+            LoadoutField::FinderAmpDecay => "Finder Amp Decay (PEC)",
+            LoadoutField::ProbeCost => "Probe Cost (PED)",
+            LoadoutField::ExcavatorDecay => "Excavator Decay (PEC)",
+        }
+    }
+
+    pub fn value(&self, loadout: &Loadout) -> String {
+        let text = |value: &Option<String>| value.clone().unwrap_or_default();
+        match self {
+            LoadoutField::Name => loadout.name.clone(),
+            LoadoutField::Weapon => text(&loadout.weapon),
+            LoadoutField::Decay => loadout.decay.to_string(),
+            LoadoutField::Burn => loadout.burn.to_string(),
+            LoadoutField::DamageEnhancers => loadout.damage_enhancers.to_string(),
+            LoadoutField::EconomyEnhancers => loadout.economy_enhancers.to_string(),
+            LoadoutField::Amp => text(&loadout.amp),
+            LoadoutField::AmpDecay => loadout.amp_decay.to_string(),
+            LoadoutField::AmpBurn => loadout.amp_burn.to_string(),
+            LoadoutField::Scope => text(&loadout.scope),
+            LoadoutField::ScopeDecay => loadout.scope_decay.to_string(),
+            LoadoutField::SightOne => text(&loadout.sight_one),
+            LoadoutField::SightOneDecay => loadout.sight_one_decay.to_string(),
+            LoadoutField::SightTwo => text(&loadout.sight_two),
+            LoadoutField::SightTwoDecay => loadout.sight_two_decay.to_string(),
+            LoadoutField::HealingTool => text(&loadout.healing_tool),
+            LoadoutField::HealingDecay => loadout.healing_decay.to_string(),
+            LoadoutField::HealingBurn => loadout.healing_burn.to_string(),
+            LoadoutField::Armor => text(&loadout.armor),
+            LoadoutField::ArmorProtection => loadout.armor_protection.to_string(),
+            LoadoutField::ArmorDecay => loadout.armor_decay.to_string(),
+            LoadoutField::Plates => text(&loadout.plates),
+            LoadoutField::PlateProtection => loadout.plate_protection.to_string(),
+            LoadoutField::PlateDecay => loadout.plate_decay.to_string(),
+            LoadoutField::Finder => text(&loadout.finder),
+            LoadoutField::FinderAmp => text(&loadout.finder_amp),
+            LoadoutField::Excavator => text(&loadout.excavator),
+            LoadoutField::FinderDecay => loadout.finder_decay.to_string(),
+            LoadoutField::FinderAmpDecay => loadout.finder_amp_decay.to_string(),
+            LoadoutField::ProbeCost => loadout.probe_cost.to_string(),
+            LoadoutField::ExcavatorDecay => loadout.excavator_decay.to_string(),
+        }
+    }
+
+    pub fn set(&self, loadout: &mut Loadout, input: &str) -> Result<(), String> {
+        let input = input.trim();
+        match self {
+            LoadoutField::Name => {
+                if input.is_empty() {
+                    return Err(String::from("name cannot be empty"));
+                }
+                loadout.name = input.to_string();
+            }
+            LoadoutField::Weapon => loadout.weapon = parse_item(input),
+            LoadoutField::Decay => loadout.decay = parse_decimal(input)?,
+            LoadoutField::Burn => loadout.burn = parse_count(input)?,
+            LoadoutField::DamageEnhancers => loadout.damage_enhancers = parse_enhancers(input)?,
+            LoadoutField::EconomyEnhancers => loadout.economy_enhancers = parse_enhancers(input)?,
+            LoadoutField::Amp => loadout.amp = parse_item(input),
+            LoadoutField::AmpDecay => loadout.amp_decay = parse_decimal(input)?,
+            LoadoutField::AmpBurn => loadout.amp_burn = parse_count(input)?,
+            LoadoutField::Scope => loadout.scope = parse_item(input),
+            LoadoutField::ScopeDecay => loadout.scope_decay = parse_decimal(input)?,
+            LoadoutField::SightOne => loadout.sight_one = parse_item(input),
+            LoadoutField::SightOneDecay => loadout.sight_one_decay = parse_decimal(input)?,
+            LoadoutField::SightTwo => loadout.sight_two = parse_item(input),
+            LoadoutField::SightTwoDecay => loadout.sight_two_decay = parse_decimal(input)?,
+            LoadoutField::HealingTool => loadout.healing_tool = parse_item(input),
+            LoadoutField::HealingDecay => loadout.healing_decay = parse_decimal(input)?,
+            LoadoutField::HealingBurn => loadout.healing_burn = parse_count(input)?,
+            LoadoutField::Armor => loadout.armor = parse_item(input),
+            LoadoutField::ArmorProtection => loadout.armor_protection = parse_decimal(input)?,
+            LoadoutField::ArmorDecay => loadout.armor_decay = parse_decimal(input)?,
+            LoadoutField::Plates => loadout.plates = parse_item(input),
+            LoadoutField::PlateProtection => loadout.plate_protection = parse_decimal(input)?,
+            LoadoutField::PlateDecay => loadout.plate_decay = parse_decimal(input)?,
+            LoadoutField::Finder => loadout.finder = parse_item(input),
+            LoadoutField::FinderAmp => loadout.finder_amp = parse_item(input),
+            LoadoutField::Excavator => loadout.excavator = parse_item(input),
+            LoadoutField::FinderDecay => loadout.finder_decay = parse_decimal(input)?,
+            LoadoutField::FinderAmpDecay => loadout.finder_amp_decay = parse_decimal(input)?,
+            LoadoutField::ProbeCost => loadout.probe_cost = parse_decimal(input)?,
+            LoadoutField::ExcavatorDecay => loadout.excavator_decay = parse_decimal(input)?,
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_item(input: &str) -> Option<String> {
+    match input.is_empty() {
+        true => None,
+        false => Some(input.to_string()),
+    }
+}
+
+fn parse_decimal(input: &str) -> Result<Decimal, String> {
+    let value =
+        Decimal::from_str_exact(input).map_err(|_| format!("'{}' is not a number", input))?;
+    if value.is_sign_negative() {
+        return Err(format!("{} cannot be negative", value));
+    }
+
+    Ok(value)
+}
+
+fn parse_count(input: &str) -> Result<usize, String> {
+    input
+        .parse::<usize>()
+        .map_err(|_| format!("'{}' is not a whole number", input))
+}
+
+fn parse_enhancers(input: &str) -> Result<u8, String> {
+    match input.parse::<u8>() {
+        Ok(count) if count <= MAX_ENHANCERS => Ok(count),
+        _ => Err(format!("enhancers must be 0 to {}", MAX_ENHANCERS)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ped(value: &str) -> Decimal {
+        Decimal::from_str_exact(value).unwrap()
+    }
+
+    // Decay and ammo burn per use as listed in the item info
+    fn opalo_with_a101() -> Loadout {
+        let mut loadout = Loadout::empty("test", "Opalo / A101");
+        loadout.weapon = Some(String::from("Sollomate Opalo"));
+        loadout.decay = ped("0.650");
+        loadout.burn = 138;
+        loadout.amp = Some(String::from("Omegaton A101"));
+        loadout.amp_decay = ped("0.200");
+        loadout.amp_burn = 100;
+        loadout
+    }
+
+    fn p5a_with_a102() -> Loadout {
+        let mut loadout = Loadout::empty("test", "P5a / A102");
+        loadout.weapon = Some(String::from("Breer P5a"));
+        loadout.decay = ped("1.000");
+        loadout.burn = 190;
+        loadout.amp = Some(String::from("Omegaton A102"));
+        loadout.amp_decay = ped("0.400");
+        loadout.amp_burn = 200;
+        loadout
+    }
+
+    #[test]
+    fn empty_loadout_costs_nothing() {
+        assert_eq!(
+            Loadout::empty("test", "test").cost_per_shot(),
+            Decimal::ZERO
+        );
+    }
+
+    #[test]
+    fn opalo_with_a101_cost_per_shot() {
+        // 2.03 PEC weapon and 1.2 PEC amp
+        assert_eq!(opalo_with_a101().cost_per_shot(), ped("0.0323"));
+    }
+
+    #[test]
+    fn p5a_with_a102_cost_per_shot() {
+        // 2.9 PEC weapon and 2.4 PEC amp
+        assert_eq!(p5a_with_a102().cost_per_shot(), ped("0.053"));
+    }
+
+    #[test]
+    fn scope_and_sights_decay() {
+        let mut loadout = opalo_with_a101();
+        loadout.scope_decay = ped("0.05");
+        loadout.sight_one_decay = ped("0.02");
+        loadout.sight_two_decay = ped("0.03");
+        assert_eq!(loadout.cost_per_shot(), ped("0.0333"));
+    }
+
+    #[test]
+    fn damage_enhancers_raise_weapon_cost_only() {
+        let mut loadout = p5a_with_a102();
+        loadout.damage_enhancers = 10;
+        // weapon doubled by ten enhancers, amp unchanged: 5.8 + 2.4 PEC
+        assert_eq!(loadout.cost_per_shot(), ped("0.082"));
+    }
+
+    #[test]
+    fn economy_enhancers_lower_weapon_cost() {
+        let mut loadout = p5a_with_a102();
+        loadout.economy_enhancers = 10;
+        // weapon less 11%: 2.581 + 2.4 PEC
+        assert_eq!(loadout.cost_per_shot(), ped("0.04981"));
+    }
+
+    #[test]
+    fn damage_and_economy_enhancers_combine() {
+        let mut loadout = p5a_with_a102();
+        loadout.damage_enhancers = 5;
+        loadout.economy_enhancers = 5;
+        // 2.9 PEC * 1.5 * 0.945 + 2.4 PEC
+        assert_eq!(loadout.cost_per_shot(), ped("0.0651075"));
+    }
+}
@@ -1,3 +1,5 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 #[derive(Clone, Copy)]
@@ -8,7 +10,32 @@ pub enum LogType {
     Skills,
 }
 
-#[derive(Hash, Eq, PartialEq, Clone, Copy)]
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy)]
+pub enum Channel {
+    System,
+    Globals,
+    Local,
+    Team,
+    Society,
+    Trade,
+    Other,
+}
+
+impl Channel {
+    pub fn from_tag(tag: &str) -> Channel {
+        match tag {
+            "System" => Channel::System,
+            "Globals" => Channel::Globals,
+            "Local" => Channel::Local,
+            "Team" => Channel::Team,
+            "Society" => Channel::Society,
+            "Trade" => Channel::Trade,
+            _ => Channel::Other,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Hash, Eq, PartialEq, Clone, Copy)]
 pub enum EventType {
     SelfCrit,
     SelfHit,
@@ -25,10 +52,25 @@ pub enum EventType {
     TargetHit,
     GlobalHuntHOF,
     GlobalHunt,
+    PvpKill,
+    PvpDisable,
+    GlobalMiningHOF,
+    GlobalMining,
+    GlobalCraftingHOF,
+    GlobalCrafting,
+    GlobalItemHOF,
+    GlobalItem,
+    MiningClaim,
+    MiningNoFind,
+    CraftSuccess,
+    CraftNearSuccess,
+    CraftFail,
 }
 
 pub struct Log<'a> {
     pub line: &'a str,
+    pub timestamp: NaiveDateTime,
+    pub channel: Channel,
     pub log_type: LogType,
     pub event_type: EventType,
     pub values: Vec<String>,
@@ -37,6 +79,7 @@ pub struct Log<'a> {
 #[derive(Clone, Copy)]
 pub struct LogEvent<'a> {
     pub regex: &'a str,
+    pub channel: Channel,
     pub log_type: LogType,
     pub event_type: EventType,
 }
@@ -52,6 +95,7 @@ impl<'a> Logger<'a> {
             0,
             LogEvent {
                 regex: r"Critical hit - Additional damage! You inflicted (.*?) points of damage",
+                channel: Channel::System,
                 log_type: LogType::Combat,
                 event_type: EventType::SelfCrit,
             },
@@ -60,6 +104,7 @@ impl<'a> Logger<'a> {
             1,
             LogEvent {
                 regex: r"You inflicted (.*?) points of damage",
+                channel: Channel::System,
                 log_type: LogType::Combat,
                 event_type: EventType::SelfHit,
             },
@@ -68,6 +113,7 @@ impl<'a> Logger<'a> {
             2,
             LogEvent {
                 regex: r"You healed yourself (.*?) points",
+                channel: Channel::System,
                 log_type: LogType::Combat,
                 event_type: EventType::SelfHeal,
             },
@@ -76,6 +122,7 @@ impl<'a> Logger<'a> {
             3,
             LogEvent {
                 regex: r"Damage deflected!",
+                channel: Channel::System,
                 log_type: LogType::Combat,
                 event_type: EventType::SelfDeflect,
             },
@@ -84,6 +131,7 @@ impl<'a> Logger<'a> {
             4,
             LogEvent {
                 regex: r"You Evaded the attack",
+                channel: Channel::System,
                 log_type: LogType::Combat,
                 event_type: EventType::SelfEvade,
             },
@@ -92,6 +140,7 @@ impl<'a> Logger<'a> {
             5,
             LogEvent {
                 regex: r"You missed",
+                channel: Channel::System,
                 log_type: LogType::Combat,
                 event_type: EventType::SelfMiss,
             },
@@ -100,6 +149,7 @@ impl<'a> Logger<'a> {
             6,
             LogEvent {
                 regex: r"You have gained (.*?) experience in your (.*?) skill",
+                channel: Channel::System,
                 log_type: LogType::Skills,
                 event_type: EventType::SelfSkillGain,
             },
@@ -108,6 +158,7 @@ impl<'a> Logger<'a> {
             7,
             LogEvent {
                 regex: r"You received (.*?) x \((.*?)\) Value: (.*?) PED",
+                channel: Channel::System,
                 log_type: LogType::Loot,
                 event_type: EventType::SelfLoot,
             },
@@ -116,6 +167,7 @@ impl<'a> Logger<'a> {
             8,
             LogEvent {
                 regex: r"The target Dodged your attack",
+                channel: Channel::System,
                 log_type: LogType::Combat,
                 event_type: EventType::TargetDodge,
             },
@@ -124,6 +176,7 @@ impl<'a> Logger<'a> {
             9,
             LogEvent {
                 regex: r"The target Evaded your attack",
+                channel: Channel::System,
                 log_type: LogType::Combat,
                 event_type: EventType::TargetEvade,
             },
@@ -132,6 +185,7 @@ impl<'a> Logger<'a> {
             10,
             LogEvent {
                 regex: r"The target Jammed your attack",
+                channel: Channel::System,
                 log_type: LogType::Combat,
                 event_type: EventType::TargetJam,
             },
@@ -140,19 +194,22 @@ impl<'a> Logger<'a> {
             11,
             LogEvent {
                 regex: r"You took (.*?) points of damage",
+                channel: Channel::System,
                 log_type: LogType::Combat,
                 event_type: EventType::TargetHit,
             },
         );
         log_events.insert(12, LogEvent {
-            regex: r"\[\] (.*?) killed a creature \((.*?)\) with a value of (.*?) PED! A record has been added to the Hall of Fame!",
+            regex: r"(.*?) killed a creature \((.*?)\) with a value of (.*?) PED! A record has been added to the Hall of Fame!",
+            channel: Channel::Globals,
             log_type: LogType::Global,
             event_type: EventType::GlobalHuntHOF,
         });
         log_events.insert(
             13,
             LogEvent {
-                regex: r"\[\] (.*?) killed a creature \((.*?)\) with a value of (.*?) PED!",
+                regex: r"(.*?) killed a creature \((.*?)\) with a value of (.*?) PED!",
+                channel: Channel::Globals,
                 log_type: LogType::Global,
                 event_type: EventType::GlobalHunt,
             },
@@ -162,10 +219,126 @@ impl<'a> Logger<'a> {
             LogEvent {
                 // You were killed by the ruthless Attacker Elite Gen 03
                 regex: r"You were killed by (.*?)",
+                channel: Channel::System,
                 log_type: LogType::Combat,
                 event_type: EventType::SelfDeath,
             },
         );
+        log_events.insert(
+            15,
+            LogEvent {
+                // Killer killed Victim using a Weapon.
+                regex: r"^(.*?) killed (.*?) using a (.*?)\.$",
+                channel: Channel::Globals,
+                log_type: LogType::Combat,
+                event_type: EventType::PvpKill,
+            },
+        );
+        log_events.insert(
+            16,
+            LogEvent {
+                // Attacker DISABLED Victim using a Weapon.
+                regex: r"^(.*?) DISABLED (.*?) using a (.*?)\.$",
+                channel: Channel::Globals,
+                log_type: LogType::Combat,
+                event_type: EventType::PvpDisable,
+            },
+        );
+        log_events.insert(17, LogEvent {
+            regex: r"(.*?) found a deposit \((.*?)\) with a value of (.*?) PED! A record has been added to the Hall of Fame!",
+            channel: Channel::Globals,
+            log_type: LogType::Global,
+            event_type: EventType::GlobalMiningHOF,
+        });
+        log_events.insert(
+            18,
+            LogEvent {
+                regex: r"(.*?) found a deposit \((.*?)\) with a value of (.*?) PED!",
+                channel: Channel::Globals,
+                log_type: LogType::Global,
+                event_type: EventType::GlobalMining,
+            },
+        );
+        log_events.insert(19, LogEvent {
+            regex: r"(.*?) constructed an item \((.*?)\) worth (.*?) PED! A record has been added to the Hall of Fame!",
+            channel: Channel::Globals,
+            log_type: LogType::Global,
+            event_type: EventType::GlobalCraftingHOF,
+        });
+        log_events.insert(
+            20,
+            LogEvent {
+                regex: r"(.*?) constructed an item \((.*?)\) worth (.*?) PED!",
+                channel: Channel::Globals,
+                log_type: LogType::Global,
+                event_type: EventType::GlobalCrafting,
+            },
+        );
+        log_events.insert(21, LogEvent {
+            regex: r"(.*?) has found a rare item \((.*?)\) with a value of (.*?) PED! A record has been added to the Hall of Fame!",
+            channel: Channel::Globals,
+            log_type: LogType::Global,
+            event_type: EventType::GlobalItemHOF,
+        });
+        log_events.insert(
+            22,
+            LogEvent {
+                regex: r"(.*?) has found a rare item \((.*?)\) with a value of (.*?) PED!",
+                channel: Channel::Globals,
+                log_type: LogType::Global,
+                event_type: EventType::GlobalItem,
+            },
+        );
+        log_events.insert(
+            23,
+            LogEvent {
+                // You found a Lyferite Stone deposit. Size: Ample
+                regex: r"^You found an? (.*?) deposit\. Size: (.*?)\.?$",
+                channel: Channel::System,
+                log_type: LogType::Loot,
+                event_type: EventType::MiningClaim,
+            },
+        );
+        log_events.insert(
+            24,
+            LogEvent {
+                // No resources found in this area.
+                regex: r"^No resources found",
+                channel: Channel::System,
+                log_type: LogType::Loot,
+                event_type: EventType::MiningNoFind,
+            },
+        );
+        log_events.insert(
+            25,
+            LogEvent {
+                // Success! You manufactured an item.
+                regex: r"^Success! You manufactured",
+                channel: Channel::System,
+                log_type: LogType::Loot,
+                event_type: EventType::CraftSuccess,
+            },
+        );
+        log_events.insert(
+            26,
+            LogEvent {
+                // Near success! You received some residue.
+                regex: r"^Near success!",
+                channel: Channel::System,
+                log_type: LogType::Loot,
+                event_type: EventType::CraftNearSuccess,
+            },
+        );
+        log_events.insert(
+            27,
+            LogEvent {
+                // Manufacturing failed, the materials were lost.
+                regex: r"^Manufacturing failed",
+                channel: Channel::System,
+                log_type: LogType::Loot,
+                event_type: EventType::CraftFail,
+            },
+        );
 
         return Logger { log_events };
     }
@@ -1,13 +1,23 @@
+mod analytics;
+mod config;
+mod crafting;
+mod globals;
+mod headless;
+mod journal;
 mod loadout;
 mod logger;
 mod markup;
 mod parser;
+mod replay;
 mod session;
+mod storage;
 mod tracker;
 mod ui;
 mod utils;
 
 use chrono::Local;
+use config::{Args, Command, Config, USAGE};
+use crafting::Blueprint;
 use crossterm::{
     event::{self, Event, KeyCode, KeyEventKind},
     execute,
@@ -15,14 +25,19 @@ use crossterm::{
 };
 use loadout::Loadout;
 use markup::Markup;
-use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use replay::{Replay, ReplayTarget};
 use session::{Session, Stopwatch};
-use std::io;
 use std::time::{Duration, Instant};
-use std::{path::Path, sync::mpsc::channel};
+use std::{env, io, process};
+use std::{
+    path::Path,
+    sync::mpsc::{channel, Receiver},
+};
+use storage::Storage;
 use tui::backend::CrosstermBackend;
 use tui::Terminal;
-use ui::{EditableTable, EditableTableMode};
+use ui::{DeleteTarget, EditableTable, EditableTableMode};
 
 use crate::parser::{Base as ParserBase, Parser};
 use crate::tracker::{Base as TrackerBase, Tracker};
@@ -31,23 +46,96 @@ use crate::ui::{SectionState, TrackerUI, UI};
 /*
    TODO:
    * Fix watch restarting on file change causing session file to be empty
-   * Add PVP section, KDR and stuff ,{} killed {} using a {}., {} DISABLED {} using a {}.,
    * Clean up UI module
    * Fix adding new session does not update with correct active index
    * Loot items value seems a bit off? Maybe it's getting rounded? wtf
    * Solve overflow from serde_millis, maybe write own
 */
 
+fn exit_with_error(message: String) -> ! {
+    eprintln!("eutracker-rs: {}", message);
+    process::exit(1);
+}
+
 fn main() {
-    let log_path = "C:\\Users\\sizzl\\OneDrive\\Documents\\Entropia Universe\\chat.log";
+    let mut args = Args::parse(env::args().skip(1))
+        .unwrap_or_else(|e| exit_with_error(format!("{}\n\n{}", e, USAGE)));
+    if args.help {
+        println!("{}", USAGE);
+        return;
+    }
+    let config = Config::load(&mut args).unwrap_or_else(|e| exit_with_error(e));
+    let storage = Storage::open(&config.data_dir).unwrap_or_else(|e| exit_with_error(e));
+    let migrated_count = env::current_dir()
+        .map_err(|e| e.to_string())
+        .and_then(|cwd| storage.migrate_from(&cwd))
+        .unwrap_or_else(|e| exit_with_error(format!("could not migrate existing files: {}", e)));
+
+    let mut tracker = Tracker::new(config.avatar_name.clone(), storage);
+    if migrated_count > 0 {
+        tracker.logs.push_front(format!(
+            "Moved {} existing files into {}",
+            migrated_count,
+            tracker.storage.root().display()
+        ));
+    }
+
+    let log_path = config.log_path.as_path();
+    match args.command {
+        Command::Track => {}
+        Command::Replay(replay) => {
+            let summary = replay
+                .run(&mut tracker, log_path)
+                .unwrap_or_else(|e| exit_with_error(e));
+            println!(
+                "Replayed {} events from {} lines into {}",
+                summary.event_count, summary.line_count, summary.session_name
+            );
+            return;
+        }
+        Command::Recompute(session_name) => {
+            let session_name = session_name.unwrap_or(tracker.current_session.name.clone());
+            let event_count = tracker
+                .recompute_session(&session_name)
+                .unwrap_or_else(|e| exit_with_error(e));
+            println!("Recomputed {} from {} events", session_name, event_count);
+            return;
+        }
+    }
+
     let mut parser = Parser::new();
+    // Skip everything logged before startup, replay covers older events
+    parser.get_lines_to_parse(log_path);
     let (tx, parser_receiver) = channel();
-    let mut watcher = RecommendedWatcher::new(tx, Config::default()).unwrap();
+    let mut watcher = RecommendedWatcher::new(tx, notify::Config::default())
+        .unwrap_or_else(|e| exit_with_error(format!("could not start file watcher: {}", e)));
     watcher
-        .watch(Path::new(log_path), RecursiveMode::NonRecursive)
-        .unwrap();
+        .watch(log_path, RecursiveMode::NonRecursive)
+        .unwrap_or_else(|e| {
+            exit_with_error(format!("could not watch {}: {}", log_path.display(), e))
+        });
+
+    if let Some(output) = args.headless {
+        headless::run(
+            &mut tracker,
+            parser,
+            parser_receiver,
+            log_path,
+            config.tick_rate,
+            output,
+        );
+        return;
+    }
+    run_tui(tracker, parser, parser_receiver, log_path, config.tick_rate);
+}
 
-    let mut tracker = Tracker::new(String::from("Aardvark sizz-lorr Nolin"));
+fn run_tui(
+    mut tracker: Tracker,
+    mut parser: Parser,
+    parser_receiver: Receiver<notify::Result<notify::Event>>,
+    log_path: &Path,
+    tick_rate: Duration,
+) {
     let mut sessions_vec: Vec<&Session> = tracker.sessions.values().into_iter().collect();
     sessions_vec.sort_by(|a, b| b.created_at.cmp(&a.created_at));
     let active_session_idx = sessions_vec
@@ -57,7 +145,7 @@ fn main() {
     loadouts_vec.sort_by(|a, b| b.created_at.cmp(&a.created_at));
     let active_loadout_idx = loadouts_vec
         .iter()
-        .position(|&s| s.name == tracker.current_session.loadout.name);
+        .position(|&s| s.id == tracker.current_session.loadout.id);
 
     let mut ui = TrackerUI::new(active_session_idx, active_loadout_idx);
 
@@ -68,7 +156,6 @@ fn main() {
     let mut terminal = Terminal::new(backend).unwrap();
     terminal.clear().unwrap();
 
-    let tick_rate = Duration::from_millis(250);
     let mut last_tick = Instant::now();
 
     loop {
@@ -91,7 +178,176 @@ fn main() {
         if crossterm::event::poll(timeout).unwrap() {
             if let Event::Key(key) = event::read().unwrap() {
                 if key.kind == KeyEventKind::Press {
+                    // CONFIRMING
+                    if let Some(target) = ui.pending_delete.take() {
+                        if key.code != KeyCode::Char('y') {
+                            tracker.logs.push_front("Delete cancelled".to_string());
+                            continue;
+                        }
+                        let result = match &target {
+                            DeleteTarget::Session(name) => tracker.delete_session(name),
+                            DeleteTarget::Loadout { id, .. } => tracker.delete_loadout(id),
+                        };
+                        match result {
+                            Ok(()) => tracker
+                                .logs
+                                .push_front(format!("Deleted {}", target.description())),
+                            Err(e) => tracker.logs.push_front(format!("Delete failed: {}", e)),
+                        }
+                        ui.sync_active(&tracker);
+                        continue;
+                    }
+                    if let Some(session_name) = ui.pending_recompute.take() {
+                        if key.code != KeyCode::Char('y') {
+                            tracker.logs.push_front("Recompute cancelled".to_string());
+                            continue;
+                        }
+                        match tracker.recompute_session(&session_name) {
+                            Ok(event_count) => tracker.logs.push_front(format!(
+                                "Recomputed {} from {} events",
+                                session_name, event_count
+                            )),
+                            Err(e) => tracker.logs.push_front(format!("Recompute failed: {}", e)),
+                        }
+                        continue;
+                    }
                     // EDITING
+                    if let ui::MenuItem::Session = ui.active_menu_item {
+                        if ui.session_replay_state.mode == EditableTableMode::Edit {
+                            match key.code {
+                                KeyCode::Char(c) => ui.session_replay_state.input.push(c),
+                                KeyCode::Backspace => {
+                                    ui.session_replay_state.input.pop();
+                                }
+                                KeyCode::Esc => ui.session_replay_state.close(),
+                                KeyCode::Enter => {
+                                    let target = match ui.selected_session(&tracker) {
+                                        Some(session) => {
+                                            ReplayTarget::Existing(session.name.clone())
+                                        }
+                                        None => ReplayTarget::Current,
+                                    };
+                                    let result =
+                                        Replay::parse_range(&ui.session_replay_state.input, target)
+                                            .and_then(|replay| replay.run(&mut tracker, log_path));
+                                    match result {
+                                        Ok(summary) => tracker.logs.push_front(format!(
+                                            "Replayed {} events from {} lines into {}",
+                                            summary.event_count,
+                                            summary.line_count,
+                                            summary.session_name
+                                        )),
+                                        Err(e) => {
+                                            tracker.logs.push_front(format!("Replay failed: {}", e))
+                                        }
+                                    }
+                                    ui.session_replay_state.close();
+                                }
+                                _ => {}
+                            }
+                            continue;
+                        }
+                    }
+                    if let ui::MenuItem::Loadout = ui.active_menu_item {
+                        let editor = &mut ui.loadout_editor_state;
+                        if editor.is_open() {
+                            match (editor.field_input.mode == EditableTableMode::Edit, key.code) {
+                                (true, KeyCode::Char(c)) => editor.field_input.input.push(c),
+                                (true, KeyCode::Backspace) => {
+                                    editor.field_input.input.pop();
+                                }
+                                (true, KeyCode::Esc) => {
+                                    editor.field_input.close();
+                                    editor.error = None;
+                                }
+                                (true, KeyCode::Enter) => editor.commit_field(),
+                                (false, KeyCode::Up) => editor.previous_field(),
+                                (false, KeyCode::Down) => editor.next_field(),
+                                (false, KeyCode::Esc) => editor.close(),
+                                (false, KeyCode::Enter) => match editor.selected_field() {
+                                    Some(_) => editor.edit_field(),
+                                    None => {
+                                        let draft = editor.draft.clone().unwrap();
+                                        match tracker.save_loadout(draft) {
+                                            Ok(()) => {
+                                                tracker.logs.push_front(format!(
+                                                    "Saved Loadout: {}",
+                                                    editor.draft.as_ref().unwrap().name
+                                                ));
+                                                editor.close();
+                                            }
+                                            Err(e) => editor.error = Some(e),
+                                        }
+                                    }
+                                },
+                                _ => {}
+                            }
+                            continue;
+                        }
+                    }
+                    if let ui::MenuItem::Crafting = ui.active_menu_item {
+                        if ui.blueprint_input_state.mode == EditableTableMode::Edit {
+                            match key.code {
+                                KeyCode::Char(c) => ui.blueprint_input_state.input.push(c),
+                                KeyCode::Backspace => {
+                                    ui.blueprint_input_state.input.pop();
+                                }
+                                KeyCode::Esc => ui.blueprint_input_state.close(),
+                                KeyCode::Enter => {
+                                    match Blueprint::parse(&ui.blueprint_input_state.input) {
+                                        Ok(blueprint) => {
+                                            tracker.logs.push_front(format!(
+                                                "Crafting with Blueprint: {}",
+                                                blueprint.name
+                                            ));
+                                            tracker.set_blueprint(blueprint);
+                                        }
+                                        Err(e) => tracker
+                                            .logs
+                                            .push_front(format!("Invalid blueprint: {}", e)),
+                                    }
+                                    ui.blueprint_input_state.close();
+                                }
+                                _ => {}
+                            }
+                            continue;
+                        }
+                    }
+                    if let ui::MenuItem::Globals = ui.active_menu_item {
+                        if ui.globals_filter_state.mode == EditableTableMode::Edit {
+                            match key.code {
+                                KeyCode::Char(c) => ui.globals_filter_state.input.push(c),
+                                KeyCode::Backspace => {
+                                    ui.globals_filter_state.input.pop();
+                                }
+                                KeyCode::Esc => ui.globals_filter_state.close(),
+                                KeyCode::Enter => {
+                                    ui.globals_filter = ui.globals_filter_state.input.clone();
+                                    ui.globals_table_state.select(None);
+                                    ui.globals_filter_state.close();
+                                }
+                                _ => {}
+                            }
+                            continue;
+                        }
+                    }
+                    // VIEWING
+                    let viewing = match ui.active_menu_item {
+                        ui::MenuItem::Session => ui.show_session_comparison,
+                        ui::MenuItem::Loadout => ui.show_loadout_analytics,
+                        _ => false,
+                    };
+                    if viewing
+                        && matches!(
+                            key.code,
+                            KeyCode::Char('d' | 'a' | 'e' | 'u' | 'r')
+                                | KeyCode::Up
+                                | KeyCode::Down
+                                | KeyCode::Right
+                        )
+                    {
+                        continue;
+                    }
                     match ui.active_menu_item {
                         ui::MenuItem::Markup => match ui.markup_editable_table_state.mode {
                             EditableTableMode::Edit => match key.code {
@@ -120,19 +376,141 @@ fn main() {
                         KeyCode::Char('s') => ui.active_menu_item = ui::MenuItem::Session,
                         KeyCode::Char('l') => ui.active_menu_item = ui::MenuItem::Loadout,
                         KeyCode::Char('m') => ui.active_menu_item = ui::MenuItem::Markup,
+                        KeyCode::Char('g') => ui.active_menu_item = ui::MenuItem::Globals,
+                        KeyCode::Char('c') => ui.active_menu_item = ui::MenuItem::Crafting,
+                        KeyCode::Char('b') => {
+                            if let ui::MenuItem::Crafting = ui.active_menu_item {
+                                ui.blueprint_input_state.mode = EditableTableMode::Edit;
+                            }
+                        }
                         KeyCode::Char('o') => ui.active_menu_item = ui::MenuItem::Options,
+                        KeyCode::Char('f') => {
+                            if let ui::MenuItem::Globals = ui.active_menu_item {
+                                ui.globals_filter_state.input = ui.globals_filter.clone();
+                                ui.globals_filter_state.mode = EditableTableMode::Edit;
+                            }
+                        }
+                        KeyCode::Char('d') => match ui.active_menu_item {
+                            ui::MenuItem::Session => {
+                                if let Some(session) = ui.selected_session(&tracker) {
+                                    ui.pending_delete =
+                                        Some(DeleteTarget::Session(session.name.clone()));
+                                }
+                            }
+                            ui::MenuItem::Loadout => {
+                                if let Some(loadout) = ui.selected_loadout(&tracker) {
+                                    ui.pending_delete = Some(DeleteTarget::Loadout {
+                                        id: loadout.id.clone(),
+                                        name: loadout.name.clone(),
+                                    });
+                                }
+                            }
+                            _ => {}
+                        },
+                        KeyCode::Char('a') => {
+                            let result = match ui.active_menu_item {
+                                ui::MenuItem::Session => ui
+                                    .selected_session(&tracker)
+                                    .map(|session| session.name.clone())
+                                    .map(|name| (tracker.toggle_session_archived(&name), name)),
+                                ui::MenuItem::Loadout => ui
+                                    .selected_loadout(&tracker)
+                                    .map(|loadout| (loadout.id.clone(), loadout.name.clone()))
+                                    .map(|(id, name)| (tracker.toggle_loadout_archived(&id), name)),
+                                _ => None,
+                            };
+                            match result {
+                                Some((Ok(true), name)) => {
+                                    tracker.logs.push_front(format!("Archived {}", name))
+                                }
+                                Some((Ok(false), name)) => {
+                                    tracker.logs.push_front(format!("Restored {}", name))
+                                }
+                                Some((Err(e), _)) => {
+                                    tracker.logs.push_front(format!("Archive failed: {}", e))
+                                }
+                                None => {}
+                            }
+                            ui.sync_active(&tracker);
+                        }
+                        KeyCode::Char('x') => match ui.active_menu_item {
+                            ui::MenuItem::Session | ui::MenuItem::Loadout => {
+                                tracker.show_archived = !tracker.show_archived;
+                                tracker.refresh();
+                                ui.sync_active(&tracker);
+                            }
+                            _ => {}
+                        },
+                        KeyCode::Char('v') => match ui.active_menu_item {
+                            ui::MenuItem::Session => {
+                                if ui.compared_sessions.len() < 2 {
+                                    tracker.logs.push_front(
+                                        "Mark at least two sessions to compare".to_string(),
+                                    );
+                                    continue;
+                                }
+                                ui.show_session_comparison = !ui.show_session_comparison;
+                            }
+                            ui::MenuItem::Loadout => {
+                                ui.show_loadout_analytics = !ui.show_loadout_analytics;
+                            }
+                            _ => {}
+                        },
+                        KeyCode::Char(' ') => {
+                            if let ui::MenuItem::Session = ui.active_menu_item {
+                                if let Some(session) = ui.selected_session(&tracker) {
+                                    let session_name = session.name.clone();
+                                    ui.toggle_compared_session(&session_name);
+                                }
+                            }
+                        }
+                        KeyCode::Char('e') => {
+                            if let ui::MenuItem::Loadout = ui.active_menu_item {
+                                if let Some(loadout) = ui.selected_loadout(&tracker).cloned() {
+                                    ui.loadout_editor_state.open(&loadout);
+                                }
+                            }
+                        }
+                        KeyCode::Char('r') => {
+                            if let ui::MenuItem::Session = ui.active_menu_item {
+                                ui.session_replay_state.mode = EditableTableMode::Edit;
+                            }
+                        }
+                        KeyCode::Char('t') => {
+                            if let ui::MenuItem::Session = ui.active_menu_item {
+                                let activity = tracker.current_session.activity.next();
+                                tracker.current_session.activity = activity;
+                                tracker
+                                    .logs
+                                    .push_front(format!("Session Activity: {}", activity.name()));
+                            }
+                        }
+                        KeyCode::Char('u') => {
+                            if let ui::MenuItem::Session = ui.active_menu_item {
+                                let session_name = ui
+                                    .selected_session(&tracker)
+                                    .map(|session| session.name.clone())
+                                    .unwrap_or(tracker.current_session.name.clone());
+                                ui.pending_recompute = Some(session_name);
+                            }
+                        }
                         KeyCode::Char('n') => match ui.active_menu_item {
                             ui::MenuItem::Session => {
                                 tracker.logs.push_front("Creating New Session".to_string());
                                 let date_string = Local::now().format("%Y-%m-%d_%H-%M-%S");
-                                Session::new(format!("{}_session.json", date_string).as_str());
-                                tracker.sessions = Session::fetch();
+                                Session::new(
+                                    &tracker.storage,
+                                    format!("{}_session.json", date_string).as_str(),
+                                );
+                                tracker.refresh();
+                                ui.sync_active(&tracker);
                             }
                             ui::MenuItem::Loadout => {
                                 tracker.logs.push_front("Creating New Loadout".to_string());
                                 let date_string = Local::now().format("%Y-%m-%d_%H-%M-%S");
-                                Loadout::new(date_string.to_string().as_str());
-                                tracker.loadouts = Loadout::fetch();
+                                Loadout::new(&tracker.storage, date_string.to_string().as_str());
+                                tracker.refresh();
+                                ui.sync_active(&tracker);
                             }
                             _ => {}
                         },
@@ -150,6 +528,15 @@ fn main() {
                                 &mut ui,
                                 tracker.markups.values().collect::<Vec<&Markup>>(),
                             ),
+                            ui::MenuItem::Globals => {
+                                ui.refresh_globals_view(&tracker);
+                                let globals_vec = ui.filtered_globals(&tracker);
+                                ui.previous_global(globals_vec)
+                            }
+                            ui::MenuItem::Crafting => TrackerUI::previous_blueprint(
+                                &mut ui,
+                                tracker.current_session.blueprint_names(),
+                            ),
                             _ => {}
                         },
                         KeyCode::Down => match ui.active_menu_item {
@@ -166,6 +553,15 @@ fn main() {
                                 &mut ui,
                                 tracker.markups.values().collect::<Vec<&Markup>>(),
                             ),
+                            ui::MenuItem::Globals => {
+                                ui.refresh_globals_view(&tracker);
+                                let globals_vec = ui.filtered_globals(&tracker);
+                                ui.next_global(globals_vec)
+                            }
+                            ui::MenuItem::Crafting => TrackerUI::next_blueprint(
+                                &mut ui,
+                                tracker.current_session.blueprint_names(),
+                            ),
                             _ => {}
                         },
                         KeyCode::Left => match ui.active_menu_item {
@@ -176,7 +572,7 @@ fn main() {
                             ui::MenuItem::Home => ui.home_section_state.next(),
                             ui::MenuItem::Session => {
                                 let selected_idx = ui.session_list_state.selected().unwrap();
-                                if selected_idx == ui.active_session_idx.unwrap() {
+                                if Some(selected_idx) == ui.active_session_idx {
                                     tracker
                                         .logs
                                         .push_front("Session already selected".to_string());
@@ -186,9 +582,9 @@ fn main() {
                                 if tracker.current_session.is_active {
                                     tracker.current_session.pause();
                                 }
-                                tracker.current_session.save();
+                                tracker.current_session.save(&tracker.storage);
 
-                                tracker.sessions = Session::fetch();
+                                tracker.refresh();
 
                                 let mut sessions_vec: Vec<&Session> =
                                     tracker.sessions.values().into_iter().collect();
@@ -196,31 +592,31 @@ fn main() {
                                 let new_session = sessions_vec[selected_idx].clone();
                                 ui.active_session_idx = ui.session_list_state.selected();
 
+                                tracker
+                                    .logs
+                                    .push_front(format!("Selecting Session: {}", new_session.name));
+                                tracker.select_session(new_session);
+
                                 // Set active loadout idx
                                 let mut loadouts_vec: Vec<&Loadout> =
                                     tracker.loadouts.values().into_iter().collect();
                                 loadouts_vec.sort_by(|a, b| b.created_at.cmp(&a.created_at));
                                 let active_loadout_idx = loadouts_vec
                                     .iter()
-                                    .position(|&s| s.name == new_session.loadout.name);
+                                    .position(|&s| s.id == tracker.current_session.loadout.id);
                                 ui.active_loadout_idx = active_loadout_idx;
-
-                                tracker
-                                    .logs
-                                    .push_front(format!("Selecting Session: {}", new_session.name));
-                                tracker.current_session = new_session;
                             }
                             ui::MenuItem::Loadout => {
                                 let selected_idx = ui.loadout_table_state.selected().unwrap();
-                                if selected_idx == ui.active_loadout_idx.unwrap() {
+                                if Some(selected_idx) == ui.active_loadout_idx {
                                     tracker
                                         .logs
                                         .push_front("Loadout already selected".to_string());
                                     continue;
                                 }
-                                tracker.current_session.loadout.save();
+                                tracker.current_session.loadout.save(&tracker.storage);
 
-                                tracker.loadouts = Loadout::fetch();
+                                tracker.refresh();
 
                                 let mut loadouts_vec: Vec<&Loadout> =
                                     tracker.loadouts.values().into_iter().collect();
@@ -233,6 +629,24 @@ fn main() {
                                     .push_front(format!("Selecting Loadout: {}", new_loadout.name));
                                 tracker.current_session.loadout = new_loadout;
                             }
+                            ui::MenuItem::Crafting => {
+                                let blueprint = ui
+                                    .selected_blueprint(&tracker)
+                                    .and_then(|name| tracker.current_session.blueprints.get(name))
+                                    .cloned();
+                                match blueprint {
+                                    Some(blueprint) => {
+                                        tracker.logs.push_front(format!(
+                                            "Crafting with Blueprint: {}",
+                                            blueprint.name
+                                        ));
+                                        tracker.set_blueprint(blueprint);
+                                    }
+                                    None => tracker
+                                        .logs
+                                        .push_front("Blueprint has no materials set".to_string()),
+                                }
+                            }
                             _ => {}
                         },
                         KeyCode::Char('p') => match tracker.current_session.is_active {
@@ -259,9 +673,9 @@ fn main() {
 
         if last_tick.elapsed() >= tick_rate {
             // Tracker ontick?
-            tracker.current_session.save();
-            tracker.current_session.loadout.save();
-            Markup::save(tracker.markups.clone());
+            tracker.current_session.save(&tracker.storage);
+            tracker.current_session.loadout.save(&tracker.storage);
+            Markup::save(&tracker.storage, tracker.markups.clone());
             last_tick = Instant::now();
         }
     }
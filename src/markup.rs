@@ -1,11 +1,13 @@
 use chrono::serde::ts_seconds;
 use chrono::{DateTime, Utc};
-use std::{collections::HashMap, fs::File, io::Write, path::Path};
+use std::{collections::HashMap, fs::File, io::Write};
 
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
+use crate::storage::Storage;
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Markup {
     pub name: String,
@@ -15,8 +17,8 @@ pub struct Markup {
 }
 
 impl Markup {
-    pub fn load() -> HashMap<String, Markup> {
-        let markup_file = Path::new("markups.json");
+    pub fn load(storage: &Storage) -> HashMap<String, Markup> {
+        let markup_file = storage.markups_file();
         if !markup_file.exists() {
             let default_markups: HashMap<String, Markup> = HashMap::new();
             let mut file = File::create(markup_file).unwrap();
@@ -30,8 +32,8 @@ impl Markup {
         markups
     }
 
-    pub fn save(markups: HashMap<String, Markup>) {
-        let markup_file = Path::new("markups.json");
+    pub fn save(storage: &Storage, markups: HashMap<String, Markup>) {
+        let markup_file = storage.markups_file();
         let mut file = File::create(markup_file).unwrap();
         let contents = serde_json::to_string_pretty(&markups).unwrap();
         file.write_all(contents.as_bytes()).unwrap();
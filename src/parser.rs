@@ -1,18 +1,28 @@
-use std::{fs::File, io::{BufReader, BufRead}};
-use regex::{RegexSet, Regex};
+use chrono::NaiveDateTime;
+use regex::{Regex, RegexSet};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read, Seek, SeekFrom},
+    path::Path,
+    time::SystemTime,
+};
 
-use crate::logger::{Log, Logger, LogEvent};
+use crate::logger::{Channel, Log, LogEvent, Logger};
 
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
 
 pub trait Base<'a> {
-    fn get_lines_to_parse(&mut self, log_path: &str) -> Option<Vec<String>>;
+    fn get_lines_to_parse(&mut self, log_path: &Path) -> Option<Vec<String>>;
     fn parse(&'a self, line: &'a str) -> Option<Log>;
 }
 
 pub struct Parser<'a> {
     logger: Logger<'a>,
     regex_set: RegexSet,
-    last_line: usize,
+    prefix_regex: Regex,
+    offset: Option<u64>,
+    created_at: Option<SystemTime>,
+    partial_line: Vec<u8>,
 }
 
 impl<'a> Parser<'a> {
@@ -20,58 +30,153 @@ impl<'a> Parser<'a> {
         let logger = Logger::new();
         let mut regex_vec: Vec<_> = logger.log_events.iter().collect();
         regex_vec.sort_by(|a, b| a.0.cmp(&b.0));
-        let regex_slice = regex_vec.iter().map(|log_event| log_event.1.regex).collect::<Vec<_>>();
+        let regex_slice = regex_vec
+            .iter()
+            .map(|log_event| log_event.1.regex)
+            .collect::<Vec<_>>();
 
         let regex_set = RegexSet::new(regex_slice.as_slice()).unwrap();
 
         return Parser {
             logger,
             regex_set,
-            last_line: usize::MAX,
+            prefix_regex: Regex::new(
+                r"^(\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}) \[(.*?)\] \[(.*?)\] (.*)$",
+            )
+            .unwrap(),
+            offset: None,
+            created_at: None,
+            partial_line: vec![],
         };
     }
+
+    pub fn read_range(
+        log_path: &Path,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+    ) -> Result<Vec<String>, String> {
+        let file = File::open(log_path)
+            .map_err(|e| format!("could not open {}: {}", log_path.display(), e))?;
+        let mut in_range = false;
+        let mut results = vec![];
+        for line in BufReader::new(file).split(b'\n') {
+            let line = line.map_err(|e| format!("could not read {}: {}", log_path.display(), e))?;
+            let line = String::from_utf8_lossy(&line)
+                .trim_end_matches('\r')
+                .to_string();
+            if let Some(timestamp) = parse_timestamp(&line) {
+                if timestamp > to {
+                    break;
+                }
+                in_range = timestamp >= from;
+            }
+            if in_range && !line.is_empty() {
+                results.push(line);
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+pub fn parse_timestamp(line: &str) -> Option<NaiveDateTime> {
+    line.get(..19)
+        .and_then(|timestamp| NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok())
 }
 
 impl<'a> Base<'a> for Parser<'a> {
-    fn get_lines_to_parse(self: &mut Self, log_path: &str) -> Option<Vec<String>> {
-        let file = File::open(log_path).unwrap();
-        let reader = BufReader::new(file);
-        let lines: Vec<_> = reader.lines().collect();
-        let line_count = lines.len();
-        if self.last_line == usize::MAX {
-            self.last_line = line_count;
-        }
-        if self.last_line == line_count {
+    // A trailing line without a newline is held back until it is complete,
+    // a log that shrank or was replaced is read again from the start
+    fn get_lines_to_parse(&mut self, log_path: &Path) -> Option<Vec<String>> {
+        let mut file = File::open(log_path).ok()?;
+        let metadata = file.metadata().ok()?;
+        let len = metadata.len();
+        let created_at = metadata.created().ok();
+
+        let offset = match self.offset {
+            None => {
+                self.offset = Some(len);
+                self.created_at = created_at;
+                return None;
+            }
+            Some(offset) if len < offset || created_at != self.created_at => {
+                self.created_at = created_at;
+                self.partial_line.clear();
+                0
+            }
+            Some(offset) => offset,
+        };
+        if len == offset {
+            self.offset = Some(offset);
             return None;
         }
-        let line_diff = line_count - (line_count - self.last_line);
-        let lines_to_parse = &lines[line_diff..];
-        let results: Vec<String> = lines_to_parse.into_iter().map(|v| v.as_ref().unwrap().to_string()).collect();
-        self.last_line = line_count;
-    
+
+        file.seek(SeekFrom::Start(offset)).ok()?;
+        let mut buffer = std::mem::take(&mut self.partial_line);
+        let read = file.take(len - offset).read_to_end(&mut buffer).ok()?;
+        self.offset = Some(offset + read as u64);
+
+        let complete_len = match buffer.iter().rposition(|&b| b == b'\n') {
+            Some(idx) => idx + 1,
+            None => {
+                self.partial_line = buffer;
+                return None;
+            }
+        };
+        self.partial_line = buffer.split_off(complete_len);
+
+        let results: Vec<String> = String::from_utf8_lossy(&buffer)
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect();
+
         return Some(results);
     }
 
+    // Only events logged on the line's channel are matched so chat typed by
+    // players cannot pass for combat or loot messages
     fn parse(self: &'a Self, line: &'a str) -> Option<Log> {
-        for matched_index in self.regex_set.matches(line) {
-            match matched_index {
-                _ => return capture_values(line, self.logger.log_events.get(&matched_index).unwrap()),
+        let prefix = self.prefix_regex.captures(line)?;
+        let timestamp =
+            NaiveDateTime::parse_from_str(prefix.get(1)?.as_str(), TIMESTAMP_FORMAT).ok()?;
+        let channel = Channel::from_tag(prefix.get(2)?.as_str());
+        let message = prefix.get(4)?.as_str();
+
+        // Matches come in log_events key order, so HOF and crit patterns are
+        // keyed before the plainer ones they also match
+        for matched_index in self.regex_set.matches(message) {
+            let log_event = self.logger.log_events.get(&matched_index).unwrap();
+            if log_event.channel != channel {
+                continue;
             }
+            return capture_values(line, message, timestamp, log_event);
         }
 
         return None;
     }
-
 }
 
-fn capture_values<'a>(line: &'a str, log_event: &LogEvent<'a>) -> Option<Log<'a>>  {
+fn capture_values<'a>(
+    line: &'a str,
+    message: &'a str,
+    timestamp: NaiveDateTime,
+    log_event: &LogEvent<'a>,
+) -> Option<Log<'a>> {
     let regex = Regex::new(log_event.regex).unwrap();
-    if let Some(captures) = regex.captures(line) {
+    if let Some(captures) = regex.captures(message) {
         return Some(Log {
             line,
+            timestamp,
+            channel: log_event.channel,
             log_type: log_event.log_type,
             event_type: log_event.event_type,
-            values: captures.iter().map(|v| String::from(v.unwrap().as_str())).collect::<Vec<String>>().drain(1..).collect(),
+            values: captures
+                .iter()
+                .map(|v| String::from(v.unwrap().as_str()))
+                .collect::<Vec<String>>()
+                .drain(1..)
+                .collect(),
         });
     }
 
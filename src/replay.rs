@@ -0,0 +1,135 @@
+use chrono::{Local, NaiveDateTime};
+use std::path::Path;
+
+use crate::{
+    journal::Journal,
+    markup::Markup,
+    parser::{Base as ParserBase, Parser},
+    session::Session,
+    tracker::Tracker,
+};
+
+const INPUT_FORMATS: [&str; 2] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"];
+
+pub enum ReplayTarget {
+    Current,
+    Existing(String),
+    New,
+}
+
+pub struct Replay {
+    pub from: NaiveDateTime,
+    pub to: NaiveDateTime,
+    pub target: ReplayTarget,
+}
+
+pub struct ReplaySummary {
+    pub session_name: String,
+    pub line_count: usize,
+    pub event_count: usize,
+}
+
+impl Replay {
+    pub fn new(
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        target: ReplayTarget,
+    ) -> Result<Replay, String> {
+        if from > to {
+            return Err(format!("replay start {} is after its end {}", from, to));
+        }
+
+        Ok(Replay { from, to, target })
+    }
+
+    pub fn parse_range(input: &str, target: ReplayTarget) -> Result<Replay, String> {
+        let (from, to) = input
+            .split_once("..")
+            .ok_or("expected a range like 2023-05-01 10:00..2023-05-01 12:00")?;
+        Replay::new(parse_datetime(from)?, parse_datetime(to)?, target)
+    }
+
+    pub fn run(&self, tracker: &mut Tracker, log_path: &Path) -> Result<ReplaySummary, String> {
+        let journaled_session = match &self.target {
+            ReplayTarget::Current => Some(tracker.current_session.name.as_str()),
+            ReplayTarget::Existing(name) => Some(name.as_str()),
+            ReplayTarget::New => None,
+        };
+        if let Some(session_name) = journaled_session {
+            let events = Journal::load(&tracker.storage, session_name);
+            if let (Some(first), Some(last)) = (events.first(), events.last()) {
+                if self.from <= last.timestamp && self.to >= first.timestamp {
+                    return Err(format!(
+                        "{} already holds events from {} to {}",
+                        session_name, first.timestamp, last.timestamp
+                    ));
+                }
+            }
+        }
+
+        let lines = Parser::read_range(log_path, self.from, self.to)?;
+
+        let target_session = match &self.target {
+            ReplayTarget::Current => None,
+            ReplayTarget::Existing(name) if *name == tracker.current_session.name => None,
+            ReplayTarget::Existing(name) => Some(
+                Session::load(&tracker.storage.session_file(name))
+                    .ok_or(format!("session {} does not exist", name))?,
+            ),
+            ReplayTarget::New => {
+                let date_string = Local::now().format("%Y-%m-%d_%H-%M-%S");
+                Some(Session::new(
+                    &tracker.storage,
+                    format!("{}_session.json", date_string).as_str(),
+                ))
+            }
+        };
+        let previous_session = target_session.map(|session| {
+            let previous_session = tracker.current_session.clone();
+            tracker.select_session(session);
+            previous_session
+        });
+
+        let parser = Parser::new();
+        let mut event_count = 0;
+        let mut first_event: Option<NaiveDateTime> = None;
+        let mut last_event: Option<NaiveDateTime> = None;
+        for line in lines.iter() {
+            if let Some(log) = parser.parse(line) {
+                first_event.get_or_insert(log.timestamp);
+                last_event = Some(log.timestamp);
+                tracker.record_global(&log);
+                tracker.apply(log);
+                event_count += 1;
+            }
+        }
+        if let (Some(first_event), Some(last_event)) = (first_event, last_event) {
+            tracker.current_session.elapsed_time =
+                tracker.current_session.elapsed_time + (last_event - first_event);
+        }
+        tracker.current_session.save(&tracker.storage);
+        Markup::save(&tracker.storage, tracker.markups.clone());
+
+        let session_name = tracker.current_session.name.clone();
+        if let Some(previous_session) = previous_session {
+            tracker.current_session = previous_session;
+        }
+        tracker.refresh();
+
+        Ok(ReplaySummary {
+            session_name,
+            line_count: lines.len(),
+            event_count,
+        })
+    }
+}
+
+pub fn parse_datetime(input: &str) -> Result<NaiveDateTime, String> {
+    INPUT_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(input.trim(), format).ok())
+        .ok_or(format!(
+            "invalid time '{}', expected YYYY-MM-DD HH:MM[:SS]",
+            input.trim()
+        ))
+}
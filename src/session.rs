@@ -1,13 +1,52 @@
 use chrono::serde::{ts_seconds, ts_seconds_option};
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
 use serde_with::{serde_as, DurationSeconds};
 
-use glob::glob;
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fs::File, io::Write, path::Path};
 
-use crate::loadout::Loadout;
+use crate::{
+    crafting::{Blueprint, BlueprintStats, UNKNOWN_BLUEPRINT},
+    globals::GlobalCategory,
+    journal::SessionEvent,
+    loadout::Loadout,
+    logger::EventType,
+    storage::Storage,
+    utils::{Helpers, Utils},
+};
+
+pub const KILL_LOOT_WINDOW_SECS: i64 = 2;
+pub const KILL_GLOBAL_WINDOW_SECS: i64 = 10;
+pub const UNKNOWN_MOB: &str = "Unknown";
+// The last bucket holds every pack returning at least the final bound
+pub const LOOT_PACK_BUCKETS: [u32; 6] = [25, 50, 90, 110, 200, 500];
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Default)]
+pub enum Activity {
+    #[default]
+    Hunting,
+    Mining,
+    Crafting,
+}
+
+impl Activity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Activity::Hunting => "Hunting",
+            Activity::Mining => "Mining",
+            Activity::Crafting => "Crafting",
+        }
+    }
+
+    pub fn next(&self) -> Activity {
+        match self {
+            Activity::Hunting => Activity::Mining,
+            Activity::Mining => Activity::Crafting,
+            Activity::Crafting => Activity::Hunting,
+        }
+    }
+}
 
 #[serde_as]
 #[derive(Serialize, Deserialize, Clone)]
@@ -18,28 +57,61 @@ pub struct Session {
     #[serde_as(as = "DurationSeconds<i64>")]
     pub elapsed_time: Duration,
     pub is_active: bool,
+    #[serde(default)]
+    pub activity: Activity,
 
     pub loadout: Loadout,
     pub stats: SessionStats,
     pub loot_map: HashMap<String, SessionLoot>,
     pub skill_map: HashMap<String, SessionSkill>,
+    #[serde(default)]
+    pub mob_map: HashMap<String, SessionMob>,
+    #[serde(default)]
+    pub closed_packs: LootPackStats,
+    #[serde(default)]
+    pub last_pack: Option<LootPack>,
+    #[serde(default)]
+    pub loadout_segments: Vec<LoadoutSegment>,
+    #[serde(default)]
+    pub blueprints: HashMap<String, Blueprint>,
+    #[serde(default)]
+    pub active_blueprint: Option<String>,
+    #[serde(default)]
+    pub archived: bool,
+    // Sessions tracked before journals existed only journal their tail
+    #[serde(default)]
+    pub journaled_from_start: bool,
     #[serde(with = "ts_seconds")]
     pub created_at: DateTime<Utc>,
+    // Set until the first event after a resume, so paused time is not counted
+    #[serde(skip)]
+    pub resumed: bool,
 }
 
 impl Session {
-    pub fn new(session_name: &str) -> Session {
-        let current_session_file = Path::new(session_name);
+    pub fn new(storage: &Storage, session_name: &str) -> Session {
+        let name = session_name.replace(".json", "");
+        let current_session_file = storage.session_file(&name);
         let default_session = Session {
-            name: String::from(session_name.replace(".json", "")),
+            name,
             start_time: None,
             elapsed_time: Duration::zero(),
             is_active: false,
-            loadout: Loadout::new("default"),
+            activity: Activity::Hunting,
+            loadout: Loadout::default_loadout(storage),
             stats: SessionStats::new(),
             loot_map: HashMap::new(),
             skill_map: HashMap::new(),
+            mob_map: HashMap::new(),
+            closed_packs: LootPackStats::default(),
+            last_pack: None,
+            loadout_segments: vec![],
+            blueprints: HashMap::new(),
+            active_blueprint: None,
+            archived: false,
+            journaled_from_start: true,
             created_at: Utc::now(),
+            resumed: false,
         };
 
         let mut file = File::create(current_session_file).unwrap();
@@ -59,31 +131,469 @@ impl Session {
         Some(session)
     }
 
-    pub fn fetch() -> HashMap<String, Session> {
+    pub fn fetch(storage: &Storage) -> HashMap<String, Session> {
+        let mut session_map = Session::fetch_all(storage);
+        session_map.retain(|_, session| !session.archived);
+
+        session_map
+    }
+
+    pub fn fetch_all(storage: &Storage) -> HashMap<String, Session> {
         let mut session_map: HashMap<String, Session> = HashMap::new();
-        for entry in glob("*_session.json").unwrap() {
-            if let Ok(file_path) = entry {
-                if file_path.to_str().unwrap() == "current_session.json" {
-                    continue;
+        for file_path in Storage::json_files(&storage.sessions_dir()) {
+            let session = Session::load(&file_path).unwrap();
+            session_map.insert(
+                file_path.file_stem().unwrap().to_str().unwrap().to_string(),
+                session,
+            );
+        }
+
+        session_map
+    }
+
+    pub fn apply_event(&mut self, event: &SessionEvent, user: &str) -> bool {
+        let before = SegmentSnapshot::of(&self.stats);
+        let concerns_user = self.fold_event(event, user);
+        if concerns_user {
+            let after = SegmentSnapshot::of(&self.stats);
+            self.loadout_segment(event.timestamp).add(
+                event.timestamp,
+                event.resumed,
+                &before,
+                &after,
+            );
+        }
+
+        concerns_user
+    }
+
+    fn loadout_segment(&mut self, timestamp: NaiveDateTime) -> &mut LoadoutSegment {
+        let same_loadout = self.loadout_segments.last().is_some_and(|segment| {
+            segment.loadout_id == self.loadout.id && segment.ended_at <= timestamp
+        });
+        if !same_loadout {
+            self.loadout_segments
+                .push(LoadoutSegment::new(&self.loadout, timestamp));
+        }
+
+        self.loadout_segments.last_mut().unwrap()
+    }
+
+    pub fn loadout_totals(&self) -> Vec<LoadoutSegment> {
+        let mut totals: Vec<LoadoutSegment> = vec![];
+        for segment in self.loadout_segments.iter() {
+            match totals
+                .iter_mut()
+                .find(|total| total.loadout_id == segment.loadout_id)
+            {
+                Some(total) => total.merge(segment),
+                None => totals.push(segment.clone()),
+            }
+        }
+
+        totals
+    }
+
+    fn fold_event(&mut self, event: &SessionEvent, user: &str) -> bool {
+        let mut concerns_user = true;
+        match event.event_type {
+            EventType::SelfCrit => {
+                self.stats.self_attack_count += 1;
+                self.stats.self_crit_count += 1;
+                self.stats.self_total_damage +=
+                    Decimal::from_str_exact(event.values.first().unwrap()).unwrap();
+                self.stats.self_total_crit_damage +=
+                    Decimal::from_str_exact(event.values.first().unwrap()).unwrap();
+                self.add_shot_cost();
+            }
+            EventType::SelfHit => {
+                self.stats.self_attack_count += 1;
+                self.stats.self_total_damage +=
+                    Decimal::from_str_exact(event.values.first().unwrap()).unwrap();
+                self.add_shot_cost();
+            }
+            EventType::SelfHeal => {
+                self.stats.self_total_heal +=
+                    Decimal::from_str_exact(event.values.first().unwrap()).unwrap();
+                let heal_cost = self.loadout.cost_per_heal();
+                self.stats.self_heal_count += 1;
+                self.stats.heal_cost += heal_cost;
+                self.stats.total_cost += heal_cost;
+            }
+            EventType::SelfDeflect => {
+                self.stats.self_deflect_count += 1;
+                self.stats.target_attack_count += 1;
+                self.add_armor_cost();
+            }
+            EventType::SelfEvade => {
+                self.stats.self_evade_count += 1;
+                self.stats.target_attack_count += 1;
+            }
+            EventType::SelfMiss => {
+                self.stats.self_attack_count += 1;
+                self.stats.self_attack_miss_count += 1;
+                self.add_shot_cost();
+            }
+            EventType::SelfSkillGain => {
+                let exp_gain = Decimal::from_str_exact(event.values.first().unwrap()).unwrap();
+                let skill = event.values.get(1).unwrap();
+                self.stats.self_total_exp_gain += exp_gain;
+                if self.skill_map.contains_key(skill) {
+                    self.skill_map.get_mut(skill).unwrap().exp_gain += exp_gain;
+                } else {
+                    self.skill_map.insert(
+                        skill.to_string(),
+                        SessionSkill {
+                            name: skill.to_string(),
+                            exp_gain,
+                        },
+                    );
+                }
+            }
+            EventType::SelfLoot => {
+                let loot = event.values.first().unwrap();
+                let quantity = event.values.get(1).unwrap().parse::<usize>().unwrap();
+                let value = Decimal::from_str_exact(event.values.get(2).unwrap()).unwrap();
+                self.stats.tt_profit += value;
+
+                if self.loot_map.contains_key(loot) {
+                    self.loot_map.get_mut(loot).unwrap().tt_value += value;
+                    self.loot_map.get_mut(loot).unwrap().count += quantity;
+                } else {
+                    self.loot_map.insert(
+                        loot.to_string(),
+                        SessionLoot {
+                            name: loot.to_string(),
+                            tt_value: value,
+                            count: quantity,
+                        },
+                    );
+                }
+                match event.activity {
+                    Activity::Hunting => self.track_kill_loot(event.timestamp, value),
+                    Activity::Mining => self.stats.mining.loot_value += value,
+                    Activity::Crafting => {
+                        let blueprint = event
+                            .blueprint
+                            .clone()
+                            .unwrap_or(String::from(UNKNOWN_BLUEPRINT));
+                        self.stats
+                            .blueprint_map
+                            .entry(blueprint.clone())
+                            .or_insert_with(|| BlueprintStats::new(&blueprint))
+                            .add_output(loot, value);
+                    }
+                }
+            }
+            EventType::CraftSuccess | EventType::CraftNearSuccess | EventType::CraftFail => {
+                let blueprint = event
+                    .blueprint
+                    .clone()
+                    .unwrap_or(String::from(UNKNOWN_BLUEPRINT));
+                let inputs = self
+                    .blueprints
+                    .get(&blueprint)
+                    .map(|blueprint| blueprint.inputs.clone())
+                    .unwrap_or_default();
+                self.stats.total_cost += inputs.iter().map(|input| input.tt_value).sum::<Decimal>();
+                self.stats
+                    .blueprint_map
+                    .entry(blueprint.clone())
+                    .or_insert_with(|| BlueprintStats::new(&blueprint))
+                    .add_attempt(event.event_type, &inputs);
+            }
+            EventType::SelfDeath => {
+                self.stats.self_death_count += 1;
+            }
+            EventType::TargetDodge => {
+                self.stats.target_dodge_count += 1;
+                self.stats.self_attack_count += 1;
+                self.stats.self_attack_miss_count += 1;
+                self.add_shot_cost();
+            }
+            EventType::TargetEvade => {
+                self.stats.target_evade_count += 1;
+                self.stats.self_attack_count += 1;
+                self.stats.self_attack_miss_count += 1;
+                self.add_shot_cost();
+            }
+            EventType::TargetJam => {
+                self.stats.target_jam_count += 1;
+                self.stats.self_attack_count += 1;
+                self.stats.self_attack_miss_count += 1;
+                self.add_shot_cost();
+            }
+            EventType::TargetHit => {
+                self.stats.target_attack_count += 1;
+                self.stats.target_total_damage +=
+                    Decimal::from_str_exact(event.values.first().unwrap()).unwrap();
+                self.add_armor_cost();
+            }
+            EventType::GlobalHuntHOF
+            | EventType::GlobalHunt
+            | EventType::GlobalMiningHOF
+            | EventType::GlobalMining
+            | EventType::GlobalCraftingHOF
+            | EventType::GlobalCrafting
+            | EventType::GlobalItemHOF
+            | EventType::GlobalItem => {
+                let global_user: String = event.values.first().unwrap().to_string();
+                if global_user == user {
+                    let (category, hof) =
+                        GlobalCategory::from_event_type(event.event_type).unwrap();
+                    let global_value =
+                        Decimal::from_str_exact(event.values.get(2).unwrap()).unwrap();
+                    self.stats.global_count += 1;
+                    self.stats.total_global_gain += global_value;
+                    if hof {
+                        self.stats.hof_count += 1;
+                        self.stats.total_hof_gain += global_value;
+                    }
+                    self.stats
+                        .global_category_map
+                        .entry(category)
+                        .or_default()
+                        .add(global_value, hof);
+                    if category == GlobalCategory::Hunting {
+                        self.attribute_kill(event.timestamp, event.values.get(1).unwrap());
+                    }
+                } else {
+                    concerns_user = false;
+                }
+            }
+            EventType::MiningClaim => {
+                let resource = event.values.first().unwrap();
+                let size = event.values.get(1).unwrap();
+                self.add_drop_cost();
+                let extraction_cost = self.loadout.cost_per_extraction();
+                self.stats.total_cost += extraction_cost;
+                self.stats.mining.claim_count += 1;
+                self.stats.mining.extraction_cost += extraction_cost;
+                *self
+                    .stats
+                    .mining
+                    .resource_map
+                    .entry(resource.to_string())
+                    .or_default() += 1;
+                *self
+                    .stats
+                    .mining
+                    .claim_size_map
+                    .entry(size.to_string())
+                    .or_default() += 1;
+            }
+            EventType::MiningNoFind => {
+                self.add_drop_cost();
+            }
+            EventType::PvpKill | EventType::PvpDisable => {
+                let attacker = event.values.first().unwrap();
+                let victim = event.values.get(1).unwrap();
+                let weapon = event.values.get(2).unwrap();
+                let is_kill = event.event_type == EventType::PvpKill;
+                if attacker == user {
+                    let tallies = [
+                        self.stats
+                            .pvp_opponent_map
+                            .entry(victim.to_string())
+                            .or_default(),
+                        self.stats
+                            .pvp_own_weapon_map
+                            .entry(weapon.to_string())
+                            .or_default(),
+                    ];
+                    for tally in tallies {
+                        match is_kill {
+                            true => tally.kills += 1,
+                            false => tally.disables += 1,
+                        }
+                    }
+                    match is_kill {
+                        true => self.stats.pvp_kill_count += 1,
+                        false => self.stats.pvp_disable_count += 1,
+                    }
+                } else if victim == user {
+                    let tallies = [
+                        self.stats
+                            .pvp_opponent_map
+                            .entry(attacker.to_string())
+                            .or_default(),
+                        self.stats
+                            .pvp_opponent_weapon_map
+                            .entry(weapon.to_string())
+                            .or_default(),
+                    ];
+                    for tally in tallies {
+                        match is_kill {
+                            true => tally.deaths += 1,
+                            false => tally.disabled += 1,
+                        }
+                    }
+                    match is_kill {
+                        true => self.stats.pvp_death_count += 1,
+                        false => self.stats.pvp_disabled_count += 1,
+                    }
+                } else {
+                    concerns_user = false;
                 }
-                let session = Session::load(&file_path).unwrap();
-                session_map.insert(
-                    file_path.file_stem().unwrap().to_str().unwrap().to_string(),
-                    session,
-                );
             }
         }
 
-        session_map
+        concerns_user
+    }
+
+    pub fn recompute(
+        &mut self,
+        events: &[SessionEvent],
+        loadouts: &HashMap<String, Loadout>,
+        user: &str,
+    ) {
+        let session_loadout = Loadout::lookup(loadouts, &self.loadout)
+            .cloned()
+            .unwrap_or(self.loadout.clone());
+
+        self.reset_stats();
+        for event in events {
+            self.loadout = loadouts
+                .get(&event.loadout)
+                .cloned()
+                .unwrap_or(session_loadout.clone());
+            self.apply_event(event, user);
+        }
+        self.loadout = session_loadout;
     }
 
-    pub fn save(self: &Self) {
-        let file_name = format!("{}.json", &self.name);
-        let current_session_file = Path::new(file_name.as_str());
+    fn add_drop_cost(&mut self) {
+        let drop_cost = self.loadout.cost_per_drop();
+        let amp_cost = self.loadout.finder_amp_cost_per_drop();
+        self.stats.mining.drop_count += 1;
+        self.stats.mining.drop_cost += drop_cost;
+        self.stats.mining.extraction_cost += amp_cost;
+        self.stats.total_cost += drop_cost + amp_cost;
+    }
+
+    fn add_shot_cost(&mut self) {
+        let shot_cost = self.loadout.cost_per_shot();
+        self.stats.total_cost += shot_cost;
+    }
+
+    fn add_armor_cost(&mut self) {
+        let armor_cost = self.loadout.armor_cost_per_hit();
+        self.stats.armor_cost += armor_cost;
+        self.stats.total_cost += armor_cost;
+    }
+
+    pub fn blueprint_names(&self) -> Vec<&String> {
+        let mut names: Vec<&String> = self
+            .blueprints
+            .keys()
+            .chain(
+                self.stats
+                    .blueprint_map
+                    .keys()
+                    .filter(|name| !self.blueprints.contains_key(*name)),
+            )
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn reset_stats(&mut self) {
+        self.stats = SessionStats::new();
+        self.loot_map.clear();
+        self.skill_map.clear();
+        self.mob_map.clear();
+        self.closed_packs = LootPackStats::default();
+        self.last_pack = None;
+        self.loadout_segments.clear();
+    }
+
+    fn track_kill_loot(&mut self, looted_at: NaiveDateTime, value: Decimal) {
+        if let Some(pack) = self.last_pack.as_mut() {
+            // Older lines replayed into the session start a pack of their own
+            let since_pack = looted_at - pack.looted_at;
+            if since_pack >= Duration::zero()
+                && since_pack <= Duration::seconds(KILL_LOOT_WINDOW_SECS)
+            {
+                pack.looted_at = looted_at;
+                pack.tt_value += value;
+                pack.item_count += 1;
+                self.mob_map.get_mut(&pack.mob).unwrap().tt_value += value;
+                return;
+            }
+        }
+
+        let cost = self.stats.total_cost
+            - self
+                .last_pack
+                .as_ref()
+                .map(|pack| pack.total_cost_mark)
+                .unwrap_or(Decimal::ZERO);
+        let pack = LootPack {
+            mob: String::from(UNKNOWN_MOB),
+            looted_at,
+            cost,
+            tt_value: value,
+            item_count: 1,
+            total_cost_mark: self.stats.total_cost,
+        };
+        self.mob_map
+            .entry(pack.mob.clone())
+            .or_insert_with(|| SessionMob::new(&pack.mob))
+            .add_kill(&pack);
+        if let Some(closed_pack) = self.last_pack.replace(pack) {
+            self.closed_packs.add(&closed_pack);
+        }
+    }
+
+    fn attribute_kill(&mut self, global_at: NaiveDateTime, mob: &str) {
+        let kill = match self.last_pack.as_mut() {
+            Some(kill) if kill.mob == UNKNOWN_MOB => kill,
+            _ => return,
+        };
+        if (global_at - kill.looted_at).num_seconds().abs() > KILL_GLOBAL_WINDOW_SECS {
+            return;
+        }
+
+        let unknown = self.mob_map.get_mut(UNKNOWN_MOB).unwrap();
+        unknown.remove_kill(kill);
+        if unknown.kills == 0 {
+            self.mob_map.remove(UNKNOWN_MOB);
+        }
+        kill.mob = mob.to_string();
+        self.mob_map
+            .entry(kill.mob.clone())
+            .or_insert_with(|| SessionMob::new(&kill.mob))
+            .add_kill(kill);
+    }
+
+    pub fn loot_pack_stats(&self) -> LootPackStats {
+        let mut pack_stats = self.closed_packs.clone();
+        if let Some(pack) = &self.last_pack {
+            pack_stats.add(pack);
+        }
+
+        pack_stats
+    }
+
+    pub fn save(&self, storage: &Storage) {
+        let current_session_file = storage.session_file(&self.name);
         let mut file = File::create(current_session_file).unwrap();
         let contents = serde_json::to_string_pretty(self).unwrap();
         file.write_all(contents.as_bytes()).unwrap();
     }
+
+    pub fn remove(storage: &Storage, session_name: &str) -> Result<(), String> {
+        for file_path in [
+            storage.session_file(session_name),
+            storage.journal_file(session_name),
+        ] {
+            if file_path.exists() {
+                std::fs::remove_file(file_path).map_err(|e| e.to_string())?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -94,12 +604,20 @@ pub struct SessionStats {
     pub total_global_gain: Decimal,
     pub hof_count: usize,
     pub total_hof_gain: Decimal,
+    #[serde(default)]
+    pub global_category_map: HashMap<GlobalCategory, GlobalTally>,
 
     pub self_total_exp_gain: Decimal,
 
     pub self_total_crit_damage: Decimal,
     pub self_total_damage: Decimal,
     pub self_total_heal: Decimal,
+    #[serde(default)]
+    pub self_heal_count: usize,
+    #[serde(default)]
+    pub heal_cost: Decimal,
+    #[serde(default)]
+    pub armor_cost: Decimal,
     pub self_attack_miss_count: usize,
     pub self_attack_count: usize,
     pub self_crit_count: usize,
@@ -112,6 +630,26 @@ pub struct SessionStats {
     pub target_dodge_count: usize,
     pub target_evade_count: usize,
     pub target_jam_count: usize,
+
+    #[serde(default)]
+    pub pvp_kill_count: usize,
+    #[serde(default)]
+    pub pvp_death_count: usize,
+    #[serde(default)]
+    pub pvp_disable_count: usize,
+    #[serde(default)]
+    pub pvp_disabled_count: usize,
+    #[serde(default)]
+    pub pvp_opponent_map: HashMap<String, PvpTally>,
+    #[serde(default)]
+    pub pvp_own_weapon_map: HashMap<String, PvpTally>,
+    #[serde(default)]
+    pub pvp_opponent_weapon_map: HashMap<String, PvpTally>,
+
+    #[serde(default)]
+    pub mining: MiningStats,
+    #[serde(default)]
+    pub blueprint_map: HashMap<String, BlueprintStats>,
 }
 
 impl SessionStats {
@@ -123,10 +661,14 @@ impl SessionStats {
             total_global_gain: Decimal::new(0, 6),
             hof_count: 0,
             total_hof_gain: Decimal::new(0, 6),
+            global_category_map: HashMap::new(),
             self_total_exp_gain: Decimal::new(0, 6),
             self_total_crit_damage: Decimal::new(0, 6),
             self_total_damage: Decimal::new(0, 6),
             self_total_heal: Decimal::new(0, 6),
+            self_heal_count: 0,
+            heal_cost: Decimal::new(0, 6),
+            armor_cost: Decimal::new(0, 6),
             self_attack_count: 0,
             self_attack_miss_count: 0,
             self_crit_count: 0,
@@ -138,8 +680,141 @@ impl SessionStats {
             target_dodge_count: 0,
             target_evade_count: 0,
             target_jam_count: 0,
+            pvp_kill_count: 0,
+            pvp_death_count: 0,
+            pvp_disable_count: 0,
+            pvp_disabled_count: 0,
+            pvp_opponent_map: HashMap::new(),
+            pvp_own_weapon_map: HashMap::new(),
+            pvp_opponent_weapon_map: HashMap::new(),
+            mining: MiningStats::default(),
+            blueprint_map: HashMap::new(),
         };
     }
+
+    pub fn global_tally(&self, category: GlobalCategory) -> GlobalTally {
+        self.global_category_map
+            .get(&category)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn heal_per_pec(&self) -> Decimal {
+        self.self_total_heal
+            .checked_div(self.heal_cost * Decimal::ONE_HUNDRED)
+            .unwrap_or(Decimal::ZERO)
+            .trunc_with_scale(2)
+    }
+
+    pub fn damage_per_pec(&self) -> Decimal {
+        Utils::get_damage_per_pec(self.self_total_damage, self.total_cost)
+    }
+
+    // Mining and crafting keep their own costs and returns apart from the loadout
+    pub fn combat_cost(&self) -> Decimal {
+        let crafting_cost: Decimal = self
+            .blueprint_map
+            .values()
+            .flat_map(|blueprint| blueprint.input_map.values())
+            .sum();
+        self.total_cost - self.mining.cost() - crafting_cost
+    }
+
+    pub fn combat_loot(&self) -> Decimal {
+        let crafting_loot: Decimal = self
+            .blueprint_map
+            .values()
+            .flat_map(|blueprint| blueprint.output_map.values())
+            .sum();
+        self.tt_profit - self.mining.loot_value - crafting_loot
+    }
+
+    pub fn average_damage_per_hit(&self) -> Decimal {
+        let hit_count = self
+            .self_attack_count
+            .saturating_sub(self.self_attack_miss_count);
+        self.self_total_damage
+            .checked_div(Decimal::from(hit_count))
+            .unwrap_or(Decimal::ZERO)
+            .round_dp_with_strategy(2, RoundingStrategy::ToZero)
+    }
+
+    pub fn crit_damage_share(&self) -> Decimal {
+        Utils::get_percentage(self.self_total_crit_damage, self.self_total_damage)
+    }
+
+    pub fn damage_per_second(&self, elapsed: Duration) -> Decimal {
+        self.self_total_damage
+            .checked_div(Decimal::from(elapsed.num_seconds()))
+            .unwrap_or(Decimal::ZERO)
+            .round_dp_with_strategy(2, RoundingStrategy::ToZero)
+    }
+
+    pub fn pvp_kdr(&self) -> Decimal {
+        Decimal::from(self.pvp_kill_count)
+            .checked_div(Decimal::from(self.pvp_death_count.max(1)))
+            .unwrap_or(Decimal::ZERO)
+            .trunc_with_scale(2)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct MiningStats {
+    pub drop_count: usize,
+    pub claim_count: usize,
+    pub drop_cost: Decimal,
+    pub extraction_cost: Decimal,
+    pub loot_value: Decimal,
+    pub resource_map: HashMap<String, usize>,
+    pub claim_size_map: HashMap<String, usize>,
+}
+
+impl MiningStats {
+    pub fn find_rate(&self) -> Decimal {
+        Utils::get_percentage(
+            Decimal::from(self.claim_count),
+            Decimal::from(self.drop_count),
+        )
+    }
+
+    pub fn cost(&self) -> Decimal {
+        self.drop_cost + self.extraction_cost
+    }
+
+    pub fn cost_per_claim(&self) -> Decimal {
+        self.cost()
+            .checked_div(Decimal::from(self.claim_count))
+            .unwrap_or(Decimal::ZERO)
+    }
+
+    pub fn return_percentage(&self) -> Decimal {
+        Utils::get_percentage(self.loot_value, self.cost())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct GlobalTally {
+    pub count: usize,
+    pub hof_count: usize,
+    pub total_value: Decimal,
+}
+
+impl GlobalTally {
+    fn add(&mut self, value: Decimal, hof: bool) {
+        self.count += 1;
+        self.total_value += value;
+        if hof {
+            self.hof_count += 1;
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct PvpTally {
+    pub kills: usize,
+    pub deaths: usize,
+    pub disables: usize,
+    pub disabled: usize,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -149,6 +824,183 @@ pub struct SessionLoot {
     pub count: usize,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LootPack {
+    pub mob: String,
+    pub looted_at: NaiveDateTime,
+    pub cost: Decimal,
+    pub tt_value: Decimal,
+    pub item_count: usize,
+    pub total_cost_mark: Decimal,
+}
+
+impl LootPack {
+    pub fn return_percentage(&self) -> Decimal {
+        Utils::get_percentage(self.tt_value, self.cost)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct LootPackStats {
+    pub pack_count: usize,
+    pub cost: Decimal,
+    pub tt_value: Decimal,
+    pub bucket_counts: [usize; LOOT_PACK_BUCKETS.len() + 1],
+}
+
+impl LootPackStats {
+    fn add(&mut self, pack: &LootPack) {
+        self.pack_count += 1;
+        self.cost += pack.cost;
+        self.tt_value += pack.tt_value;
+        let return_percentage = pack.return_percentage();
+        let bucket = LOOT_PACK_BUCKETS
+            .iter()
+            .position(|upper| return_percentage < Decimal::from(*upper))
+            .unwrap_or(LOOT_PACK_BUCKETS.len());
+        self.bucket_counts[bucket] += 1;
+    }
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LoadoutSegment {
+    pub loadout_id: String,
+    pub loadout_name: String,
+    pub started_at: NaiveDateTime,
+    pub ended_at: NaiveDateTime,
+    #[serde_as(as = "DurationSeconds<i64>")]
+    pub active_time: Duration,
+    pub segment_count: usize,
+    pub cost: Decimal,
+    pub loot_value: Decimal,
+    pub damage: Decimal,
+    pub attack_count: usize,
+    pub crit_count: usize,
+    pub miss_count: usize,
+}
+
+impl LoadoutSegment {
+    fn new(loadout: &Loadout, started_at: NaiveDateTime) -> LoadoutSegment {
+        LoadoutSegment {
+            loadout_id: loadout.id.clone(),
+            loadout_name: loadout.name.clone(),
+            started_at,
+            ended_at: started_at,
+            active_time: Duration::zero(),
+            segment_count: 1,
+            cost: Decimal::ZERO,
+            loot_value: Decimal::ZERO,
+            damage: Decimal::ZERO,
+            attack_count: 0,
+            crit_count: 0,
+            miss_count: 0,
+        }
+    }
+
+    fn add(
+        &mut self,
+        timestamp: NaiveDateTime,
+        resumed: bool,
+        before: &SegmentSnapshot,
+        after: &SegmentSnapshot,
+    ) {
+        if !resumed {
+            self.active_time = self.active_time + (timestamp - self.ended_at);
+        }
+        self.ended_at = timestamp;
+        self.cost += after.cost - before.cost;
+        self.loot_value += after.loot_value - before.loot_value;
+        self.damage += after.damage - before.damage;
+        self.attack_count += after.attack_count - before.attack_count;
+        self.crit_count += after.crit_count - before.crit_count;
+        self.miss_count += after.miss_count - before.miss_count;
+    }
+
+    fn merge(&mut self, other: &LoadoutSegment) {
+        self.ended_at = self.ended_at.max(other.ended_at);
+        self.active_time = self.active_time + other.active_time;
+        self.segment_count += other.segment_count;
+        self.cost += other.cost;
+        self.loot_value += other.loot_value;
+        self.damage += other.damage;
+        self.attack_count += other.attack_count;
+        self.crit_count += other.crit_count;
+        self.miss_count += other.miss_count;
+    }
+
+    pub fn return_percentage(&self) -> Decimal {
+        Utils::get_percentage(self.loot_value, self.cost)
+    }
+
+    pub fn crit_rate(&self) -> Decimal {
+        Utils::get_percentage(
+            Decimal::from(self.crit_count),
+            Decimal::from(self.attack_count),
+        )
+    }
+
+    pub fn miss_rate(&self) -> Decimal {
+        Utils::get_percentage(
+            Decimal::from(self.miss_count),
+            Decimal::from(self.attack_count),
+        )
+    }
+}
+
+struct SegmentSnapshot {
+    cost: Decimal,
+    loot_value: Decimal,
+    damage: Decimal,
+    attack_count: usize,
+    crit_count: usize,
+    miss_count: usize,
+}
+
+impl SegmentSnapshot {
+    fn of(stats: &SessionStats) -> SegmentSnapshot {
+        SegmentSnapshot {
+            cost: stats.combat_cost(),
+            loot_value: stats.combat_loot(),
+            damage: stats.self_total_damage,
+            attack_count: stats.self_attack_count,
+            crit_count: stats.self_crit_count,
+            miss_count: stats.self_attack_miss_count,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SessionMob {
+    pub name: String,
+    pub kills: usize,
+    pub total_cost: Decimal,
+    pub tt_value: Decimal,
+}
+
+impl SessionMob {
+    fn new(name: &str) -> SessionMob {
+        SessionMob {
+            name: name.to_string(),
+            kills: 0,
+            total_cost: Decimal::ZERO,
+            tt_value: Decimal::ZERO,
+        }
+    }
+
+    fn add_kill(&mut self, kill: &LootPack) {
+        self.kills += 1;
+        self.total_cost += kill.cost;
+        self.tt_value += kill.tt_value;
+    }
+
+    fn remove_kill(&mut self, kill: &LootPack) {
+        self.kills -= 1;
+        self.total_cost -= kill.cost;
+        self.tt_value -= kill.tt_value;
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SessionSkill {
     pub name: String,
@@ -167,6 +1019,7 @@ impl Stopwatch for Session {
     fn start(&mut self) {
         self.start_time = Some(Utc::now());
         self.is_active = true;
+        self.resumed = true;
     }
     fn pause(&mut self) {
         self.elapsed_time =
@@ -0,0 +1,130 @@
+use glob::{glob, Pattern};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+const SESSIONS_DIR: &str = "sessions";
+const LOADOUTS_DIR: &str = "loadouts";
+const MARKUPS_FILE: &str = "markups.json";
+const GLOBALS_FILE: &str = "globals.jsonl";
+const MIGRATED_MARKER: &str = ".migrated";
+
+#[derive(Clone)]
+pub struct Storage {
+    root: PathBuf,
+}
+
+impl Storage {
+    pub fn open(root: &Path) -> Result<Storage, String> {
+        let storage = Storage {
+            root: root.to_path_buf(),
+        };
+        for dir in [storage.sessions_dir(), storage.loadouts_dir()] {
+            fs::create_dir_all(&dir)
+                .map_err(|e| format!("could not create {}: {}", dir.display(), e))?;
+        }
+
+        Ok(storage)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn sessions_dir(&self) -> PathBuf {
+        self.root.join(SESSIONS_DIR)
+    }
+
+    pub fn session_file(&self, session_name: &str) -> PathBuf {
+        self.sessions_dir().join(format!("{}.json", session_name))
+    }
+
+    pub fn journal_file(&self, session_name: &str) -> PathBuf {
+        self.sessions_dir()
+            .join(format!("{}.events.jsonl", session_name))
+    }
+
+    pub fn loadouts_dir(&self) -> PathBuf {
+        self.root.join(LOADOUTS_DIR)
+    }
+
+    pub fn loadout_file(&self, file_name: &str) -> PathBuf {
+        self.loadouts_dir().join(file_name)
+    }
+
+    pub fn markups_file(&self) -> PathBuf {
+        self.root.join(MARKUPS_FILE)
+    }
+
+    pub fn globals_file(&self) -> PathBuf {
+        self.root.join(GLOBALS_FILE)
+    }
+
+    pub fn json_files(dir: &Path) -> Vec<PathBuf> {
+        Storage::matching(dir, "*.json")
+    }
+
+    // Files already present in the storage root are never overwritten
+    pub fn migrate_from(&self, dir: &Path) -> Result<usize, String> {
+        let marker = self.root.join(MIGRATED_MARKER);
+        if marker.exists() || fs::canonicalize(dir).ok() == fs::canonicalize(&self.root).ok() {
+            return Ok(0);
+        }
+
+        let mut moves: Vec<(PathBuf, PathBuf)> = vec![];
+        for file_path in Storage::matching(dir, "*_session.json") {
+            if file_path.file_name().unwrap() == "current_session.json" {
+                continue;
+            }
+            let target = self.sessions_dir().join(file_path.file_name().unwrap());
+            moves.push((file_path, target));
+        }
+        for file_path in Storage::matching(dir, "*_loadout.json") {
+            let target = self.loadouts_dir().join(file_path.file_name().unwrap());
+            moves.push((file_path, target));
+        }
+        let markups_file = dir.join(MARKUPS_FILE);
+        if markups_file.is_file() {
+            moves.push((markups_file, self.markups_file()));
+        }
+
+        let mut moved = 0;
+        for (from, to) in moves {
+            if to.exists() {
+                continue;
+            }
+            move_file(&from, &to).map_err(|e| {
+                format!(
+                    "could not move {} to {}: {}",
+                    from.display(),
+                    to.display(),
+                    e
+                )
+            })?;
+            moved += 1;
+        }
+        fs::write(&marker, "")
+            .map_err(|e| format!("could not create {}: {}", marker.display(), e))?;
+
+        Ok(moved)
+    }
+
+    fn matching(dir: &Path, file_pattern: &str) -> Vec<PathBuf> {
+        let pattern = Path::new(&Pattern::escape(&dir.to_string_lossy())).join(file_pattern);
+        match glob(&pattern.to_string_lossy()) {
+            Ok(paths) => paths.flatten().filter(|path| path.is_file()).collect(),
+            Err(_) => vec![],
+        }
+    }
+}
+
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    // rename fails across filesystems, fall back to copying the file over
+    if fs::rename(from, to).is_err() {
+        fs::copy(from, to)?;
+        fs::remove_file(from)?;
+    }
+
+    Ok(())
+}
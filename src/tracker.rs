@@ -1,17 +1,17 @@
 use chrono::Local;
 use chrono::Utc;
 use rust_decimal::prelude::*;
-use std::{
-    collections::{HashMap, VecDeque},
-    ops::Deref,
-    path::Path,
-};
+use std::collections::{HashMap, VecDeque};
 
 use crate::{
+    crafting::Blueprint,
+    globals::{GlobalHistory, GlobalRecord},
+    journal::{Journal, SessionEvent},
     loadout::Loadout,
     logger::{EventType, Log},
     markup::Markup,
-    session::{Session, SessionLoot, SessionSkill},
+    session::Session,
+    storage::Storage,
 };
 
 pub trait Base {
@@ -20,35 +20,33 @@ pub trait Base {
 
 pub struct Tracker {
     pub user: String,
+    pub storage: Storage,
 
     pub current_session: Session,
     pub loadouts: HashMap<String, Loadout>,
     pub sessions: HashMap<String, Session>,
     pub markups: HashMap<String, Markup>,
+    pub globals: GlobalHistory,
+    pub show_archived: bool,
     pub logs: VecDeque<String>,
 }
 
 impl Tracker {
-    pub fn new(user: String) -> Tracker {
-        let sessions = Session::fetch();
+    pub fn new(user: String, storage: Storage) -> Tracker {
+        let sessions = Session::fetch(&storage);
         let mut sessions_vec: Vec<&Session> = sessions.values().into_iter().collect();
         sessions_vec.sort_by(|a, b| b.created_at.cmp(&a.created_at));
         let default_session = sessions_vec.get(0);
 
-        let loadouts = Loadout::fetch();
+        let mut loadouts = Loadout::fetch(&storage);
 
         if default_session.is_some() {
-            let default_load_session_file = format!("{}.json", sessions_vec.get(0).unwrap().name);
+            let default_load_session_file =
+                storage.session_file(&sessions_vec.first().unwrap().name);
 
-            match Session::load(Path::new(default_load_session_file.as_str())) {
+            match Session::load(&default_load_session_file) {
                 Some(mut session) => {
-                    let mut loadouts_vec: Vec<&Loadout> = loadouts.values().into_iter().collect();
-                    loadouts_vec.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-                    let active_loadout_idx = loadouts_vec
-                        .iter()
-                        .position(|&s| s.name == session.loadout.name);
-
-                    session.loadout = loadouts_vec[active_loadout_idx.unwrap()].deref().clone();
+                    Tracker::resolve_loadout(&storage, &mut loadouts, &mut session);
                     session.is_active = false;
 
                     return Tracker {
@@ -56,7 +54,10 @@ impl Tracker {
                         current_session: session,
                         loadouts,
                         sessions,
-                        markups: Markup::load(),
+                        markups: Markup::load(&storage),
+                        globals: GlobalHistory::load(&storage),
+                        show_archived: false,
+                        storage,
                         logs: VecDeque::with_capacity(75),
                     };
                 }
@@ -65,11 +66,15 @@ impl Tracker {
                     return Tracker {
                         user,
                         current_session: Session::new(
+                            &storage,
                             format!("{}_session.json", date_string).as_str(),
                         ),
                         loadouts,
-                        sessions: Session::fetch(),
-                        markups: Markup::load(),
+                        sessions: Session::fetch(&storage),
+                        markups: Markup::load(&storage),
+                        globals: GlobalHistory::load(&storage),
+                        show_archived: false,
+                        storage,
                         logs: VecDeque::with_capacity(75),
                     };
                 }
@@ -78,10 +83,16 @@ impl Tracker {
         let date_string = Local::now().format("%Y-%m-%d_%H-%M-%S");
         return Tracker {
             user,
-            current_session: Session::new(format!("{}_session.json", date_string).as_str()),
-            loadouts,
-            sessions: Session::fetch(),
-            markups: Markup::load(),
+            current_session: Session::new(
+                &storage,
+                format!("{}_session.json", date_string).as_str(),
+            ),
+            loadouts: Loadout::fetch(&storage),
+            sessions: Session::fetch(&storage),
+            markups: Markup::load(&storage),
+            globals: GlobalHistory::load(&storage),
+            show_archived: false,
+            storage,
             logs: VecDeque::with_capacity(75),
         };
     }
@@ -89,157 +100,227 @@ impl Tracker {
 
 impl Base for Tracker {
     fn track(&mut self, log: Log) -> &Tracker {
+        self.record_global(&log);
         if !self.current_session.is_active {
             return self;
         }
 
-        let mut push_to_logs = true;
-        match log.event_type {
-            EventType::SelfCrit => {
-                self.current_session.stats.self_attack_count += 1;
-                self.current_session.stats.self_crit_count += 1;
-                self.current_session.stats.self_total_damage +=
-                    Decimal::from_str_exact(log.values.get(0).unwrap()).unwrap();
-                self.current_session.stats.self_total_crit_damage +=
-                    Decimal::from_str_exact(log.values.get(0).unwrap()).unwrap();
-                self.current_session.stats.total_cost +=
-                    Decimal::from(self.current_session.loadout.burn)
-                        .checked_div(
-                            Decimal::from(10000)
-                                + self.current_session.loadout.decay * Decimal::new(1, 2),
-                        )
-                        .unwrap_or(Decimal::ZERO);
-            }
-            EventType::SelfHit => {
-                self.current_session.stats.self_attack_count += 1;
-                self.current_session.stats.self_total_damage +=
-                    Decimal::from_str_exact(log.values.get(0).unwrap()).unwrap();
-                self.current_session.stats.total_cost +=
-                    Decimal::from(self.current_session.loadout.burn)
-                        .checked_div(
-                            Decimal::from(10000)
-                                + self.current_session.loadout.decay * Decimal::new(1, 2),
-                        )
-                        .unwrap_or(Decimal::ZERO);
-            }
-            EventType::SelfHeal => {
-                self.current_session.stats.self_total_heal +=
-                    Decimal::from_str_exact(log.values.get(0).unwrap()).unwrap();
-            }
-            EventType::SelfDeflect => {
-                self.current_session.stats.self_deflect_count += 1;
-                self.current_session.stats.target_attack_count += 1;
-            }
-            EventType::SelfEvade => {
-                self.current_session.stats.self_evade_count += 1;
-                self.current_session.stats.target_attack_count += 1;
-            }
-            EventType::SelfMiss => {
-                self.current_session.stats.self_attack_count += 1;
-                self.current_session.stats.self_attack_miss_count += 1;
-            }
-            EventType::SelfSkillGain => {
-                let exp_gain = Decimal::from_str_exact(log.values.get(0).unwrap()).unwrap();
-                let skill = log.values.get(1).unwrap();
-                self.current_session.stats.self_total_exp_gain += exp_gain;
-                if self.current_session.skill_map.contains_key(skill) {
-                    self.current_session
-                        .skill_map
-                        .get_mut(skill)
-                        .unwrap()
-                        .exp_gain += exp_gain;
-                } else {
-                    self.current_session.skill_map.insert(
-                        skill.to_string(),
-                        SessionSkill {
-                            name: skill.to_string(),
-                            exp_gain,
-                        },
-                    );
+        self.apply(log)
+    }
+}
+
+impl Tracker {
+    pub fn recompute_session(&mut self, session_name: &str) -> Result<usize, String> {
+        let events = Journal::load(&self.storage, session_name);
+        let loadouts = Loadout::fetch_all(&self.storage);
+        let mut session = match session_name == self.current_session.name {
+            true => self.current_session.clone(),
+            false => Session::load(&self.storage.session_file(session_name))
+                .ok_or(format!("session {} does not exist", session_name))?,
+        };
+        if events.is_empty() {
+            return Err(format!("session {} has no journaled events", session_name));
+        }
+        if !session.journaled_from_start {
+            return Err(format!(
+                "session {} was tracked before it had a journal",
+                session_name
+            ));
+        }
+        session.recompute(&events, &loadouts, &self.user);
+        session.save(&self.storage);
+        if session_name == self.current_session.name {
+            self.current_session = session;
+        }
+        self.refresh();
+
+        Ok(events.len())
+    }
+
+    pub fn set_blueprint(&mut self, blueprint: Blueprint) {
+        for input in blueprint.inputs.iter() {
+            self.ensure_markup(&input.name);
+        }
+        self.current_session.active_blueprint = Some(blueprint.name.clone());
+        self.current_session
+            .blueprints
+            .insert(blueprint.name.clone(), blueprint);
+    }
+
+    pub fn live_sessions(&self) -> impl Iterator<Item = &Session> {
+        self.sessions
+            .values()
+            .filter(|session| session.name != self.current_session.name)
+            .chain(std::iter::once(&self.current_session))
+    }
+
+    fn resolve_loadout(
+        storage: &Storage,
+        loadouts: &mut HashMap<String, Loadout>,
+        session: &mut Session,
+    ) {
+        let saved_loadout = Loadout::lookup(loadouts, &session.loadout)
+            .cloned()
+            .or_else(|| {
+                Loadout::load(&storage.loadout_file(&Loadout::file_name(&session.loadout.id)))
+            });
+        match saved_loadout {
+            Some(loadout) => session.loadout = loadout,
+            None => {
+                if session.loadout.id.is_empty() {
+                    session.loadout.id = Loadout::new_id();
                 }
+                session.loadout.save(storage);
+                loadouts.insert(session.loadout.id.clone(), session.loadout.clone());
             }
-            EventType::SelfLoot => {
-                let loot = log.values.get(0).unwrap();
-                let quantity = log.values.get(1).unwrap().parse::<usize>().unwrap();
-                let value = Decimal::from_str_exact(log.values.get(2).unwrap()).unwrap();
-                self.current_session.stats.tt_profit += value;
-
-                if !self.markups.contains_key(loot) {
-                    self.markups.insert(
-                        loot.to_string(),
-                        Markup {
-                            name: loot.to_string(),
-                            value: Decimal::new(100, 2),
-                            created_at: Utc::now(),
-                        },
-                    );
-                }
+        }
+    }
 
-                if self.current_session.loot_map.contains_key(loot) {
-                    self.current_session
-                        .loot_map
-                        .get_mut(loot)
-                        .unwrap()
-                        .tt_value += value;
-                    self.current_session.loot_map.get_mut(loot).unwrap().count += quantity;
-                } else {
-                    self.current_session.loot_map.insert(
-                        loot.to_string(),
-                        SessionLoot {
-                            name: loot.to_string(),
-                            tt_value: value,
-                            count: quantity,
-                        },
-                    );
+    pub fn select_session(&mut self, mut session: Session) {
+        Tracker::resolve_loadout(&self.storage, &mut self.loadouts, &mut session);
+        self.current_session = session;
+    }
+
+    pub fn save_loadout(&mut self, loadout: Loadout) -> Result<(), String> {
+        if self
+            .loadouts
+            .values()
+            .any(|l| l.id != loadout.id && l.name == loadout.name)
+        {
+            return Err(format!("a loadout named {} already exists", loadout.name));
+        }
+
+        let previous_name = self
+            .loadouts
+            .get(&loadout.id)
+            .map(|saved| saved.name.clone())
+            .filter(|name| *name != loadout.name);
+        loadout.save(&self.storage);
+        if let Some(previous_name) = previous_name {
+            for session in self.sessions.values_mut() {
+                let uses_loadout = session.loadout.id == loadout.id
+                    || (session.loadout.id.is_empty() && session.loadout.name == previous_name);
+                if uses_loadout && session.name != self.current_session.name {
+                    session.loadout.id = loadout.id.clone();
+                    session.loadout.name = loadout.name.clone();
+                    session.save(&self.storage);
                 }
             }
-            EventType::SelfDeath => {
-                self.current_session.stats.self_death_count += 1;
-            }
-            EventType::TargetDodge => {
-                self.current_session.stats.target_dodge_count += 1;
-                self.current_session.stats.self_attack_count += 1;
-                self.current_session.stats.self_attack_miss_count += 1;
-            }
-            EventType::TargetEvade => {
-                self.current_session.stats.target_evade_count += 1;
-                self.current_session.stats.self_attack_count += 1;
-                self.current_session.stats.self_attack_miss_count += 1;
-            }
-            EventType::TargetJam => {
-                self.current_session.stats.target_jam_count += 1;
-                self.current_session.stats.self_attack_count += 1;
-                self.current_session.stats.self_attack_miss_count += 1;
-            }
-            EventType::TargetHit => {
-                self.current_session.stats.target_attack_count += 1;
-                self.current_session.stats.target_total_damage +=
-                    Decimal::from_str_exact(log.values.get(0).unwrap()).unwrap();
-            }
-            EventType::GlobalHuntHOF => {
-                let global_user: String = log.values.get(0).unwrap().to_string();
-                if global_user == self.user {
-                    let global_value = Decimal::from_str_exact(log.values.get(2).unwrap()).unwrap();
-                    self.current_session.stats.global_count += 1;
-                    self.current_session.stats.hof_count += 1;
-                    self.current_session.stats.total_global_gain += global_value;
-                    self.current_session.stats.total_hof_gain += global_value;
-                } else {
-                    push_to_logs = false;
-                }
+        }
+        if self.current_session.loadout.id == loadout.id {
+            self.current_session.loadout = loadout;
+            self.current_session.save(&self.storage);
+        }
+        self.refresh();
+
+        Ok(())
+    }
+
+    pub fn refresh(&mut self) {
+        match self.show_archived {
+            true => {
+                self.sessions = Session::fetch_all(&self.storage);
+                self.loadouts = Loadout::fetch_all(&self.storage);
             }
-            EventType::GlobalHunt => {
-                let global_user: String = log.values.get(0).unwrap().to_string();
-                if global_user == self.user {
-                    let global_value = Decimal::from_str_exact(log.values.get(2).unwrap()).unwrap();
-                    self.current_session.stats.global_count += 1;
-                    self.current_session.stats.total_global_gain += global_value;
-                } else {
-                    push_to_logs = false;
-                }
+            false => {
+                self.sessions = Session::fetch(&self.storage);
+                self.loadouts = Loadout::fetch(&self.storage);
             }
         }
+    }
+
+    pub fn toggle_session_archived(&mut self, session_name: &str) -> Result<bool, String> {
+        if session_name == self.current_session.name {
+            return Err(String::from("the current session cannot be archived"));
+        }
+        let mut session = Session::load(&self.storage.session_file(session_name))
+            .ok_or(format!("session {} does not exist", session_name))?;
+        session.archived = !session.archived;
+        session.save(&self.storage);
+        self.refresh();
+
+        Ok(session.archived)
+    }
+
+    pub fn toggle_loadout_archived(&mut self, id: &str) -> Result<bool, String> {
+        if id == self.current_session.loadout.id {
+            return Err(String::from("the current loadout cannot be archived"));
+        }
+        let mut loadout = Loadout::load(&self.storage.loadout_file(&Loadout::file_name(id)))
+            .ok_or(format!("loadout {} does not exist", id))?;
+        loadout.archived = !loadout.archived;
+        loadout.save(&self.storage);
+        self.refresh();
+
+        Ok(loadout.archived)
+    }
+
+    pub fn delete_session(&mut self, session_name: &str) -> Result<(), String> {
+        if session_name == self.current_session.name {
+            return Err(String::from("the current session cannot be deleted"));
+        }
+        Session::remove(&self.storage, session_name)?;
+        self.refresh();
+
+        Ok(())
+    }
+
+    pub fn delete_loadout(&mut self, id: &str) -> Result<(), String> {
+        if id == self.current_session.loadout.id {
+            return Err(String::from("the current loadout cannot be deleted"));
+        }
+        // Sessions restore a missing loadout from their own copy
+        let loadout = Loadout::load(&self.storage.loadout_file(&Loadout::file_name(id)))
+            .ok_or(format!("loadout {} does not exist", id))?;
+        let loadouts = HashMap::from([(loadout.id.clone(), loadout)]);
+        let session_count = Session::fetch_all(&self.storage)
+            .values()
+            .filter(|session| Loadout::lookup(&loadouts, &session.loadout).is_some())
+            .count();
+        if session_count > 0 {
+            return Err(format!(
+                "the loadout is used by {} sessions, archive it instead",
+                session_count
+            ));
+        }
+        Loadout::remove(&self.storage, id)?;
+        self.refresh();
+
+        Ok(())
+    }
+
+    fn ensure_markup(&mut self, item: &str) {
+        if !self.markups.contains_key(item) {
+            self.markups.insert(
+                item.to_string(),
+                Markup {
+                    name: item.to_string(),
+                    value: Decimal::new(100, 2),
+                    created_at: Utc::now(),
+                },
+            );
+        }
+    }
+
+    // The same global is only recorded once so replaying a log is harmless
+    pub fn record_global(&mut self, log: &Log) {
+        let record = match GlobalRecord::from_log(log) {
+            Some(record) => record,
+            None => return,
+        };
+        self.globals.insert(&self.storage, record);
+    }
+
+    pub fn apply(&mut self, log: Log) -> &Tracker {
+        let event = SessionEvent::from_log(&log, &self.current_session);
+        if let EventType::SelfLoot = event.event_type {
+            self.ensure_markup(event.values.first().unwrap());
+        }
+
+        let push_to_logs = self.current_session.apply_event(&event, &self.user);
         if push_to_logs {
+            Journal::append(&self.storage, &self.current_session.name, &event);
+            self.current_session.resumed = false;
             self.logs.push_front(log.line.to_string());
         }
         self.logs.truncate(75);
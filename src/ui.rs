@@ -1,32 +1,43 @@
-use std::{collections::HashMap, ops::Mul, usize};
+use std::{cmp::Reverse, collections::HashMap, ops::Mul, usize};
 
 use rust_decimal::Decimal;
 use tui::{
     backend::Backend,
-    layout::{Alignment, Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Span, Spans},
     widgets::{
-        Block, Borders, Cell, List, ListItem, ListState, Paragraph, Row, Table, TableState, Tabs,
-        Wrap,
+        Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Table, TableState,
+        Tabs, Wrap,
     },
     Frame,
 };
 
 use crate::{
-    loadout::Loadout,
+    analytics::{LoadoutLifetime, SessionComparison},
+    crafting::BlueprintStats,
+    globals::{GlobalCategory, GlobalRecord},
+    loadout::{Loadout, LoadoutField},
     markup::Markup,
-    session::{Session, SessionLoot, SessionSkill, Stopwatch},
+    parser::TIMESTAMP_FORMAT,
+    session::{
+        Activity, PvpTally, Session, SessionLoot, SessionMob, SessionSkill, Stopwatch,
+        LOOT_PACK_BUCKETS,
+    },
     tracker::Tracker,
     utils::{Helpers, Utils},
 };
 
+const LOOT_PACK_BAR_WIDTH: usize = 12;
+
 #[derive(Clone, Copy)]
 pub enum MenuItem {
     Home,
     Session,
     Loadout,
     Markup,
+    Globals,
+    Crafting,
     Options,
 }
 
@@ -37,7 +48,9 @@ impl From<MenuItem> for usize {
             MenuItem::Session => 1,
             MenuItem::Loadout => 2,
             MenuItem::Markup => 3,
-            MenuItem::Options => 4,
+            MenuItem::Globals => 4,
+            MenuItem::Crafting => 5,
+            MenuItem::Options => 6,
         }
     }
 }
@@ -46,15 +59,18 @@ impl From<MenuItem> for usize {
 pub enum HomeSection {
     None,
     Skills,
+    Activity,
     LootSummary,
+    LootPacks,
     LootDetails,
     CombatSelf,
     CombatTarget,
+    Pvp,
 }
 
 impl HomeSection {
     fn count() -> usize {
-        return 6;
+        9
     }
 }
 
@@ -63,10 +79,13 @@ impl From<HomeSection> for usize {
         match input {
             HomeSection::None => 0,
             HomeSection::Skills => 1,
-            HomeSection::LootSummary => 2,
-            HomeSection::LootDetails => 3,
-            HomeSection::CombatSelf => 4,
-            HomeSection::CombatTarget => 5,
+            HomeSection::Activity => 2,
+            HomeSection::LootSummary => 3,
+            HomeSection::LootPacks => 4,
+            HomeSection::LootDetails => 5,
+            HomeSection::CombatSelf => 6,
+            HomeSection::CombatTarget => 7,
+            HomeSection::Pvp => 8,
         }
     }
 }
@@ -95,6 +114,13 @@ pub struct EditableTableState {
     pub input: String,
 }
 
+impl EditableTableState {
+    pub fn close(&mut self) {
+        self.input = String::new();
+        self.mode = EditableTableMode::View;
+    }
+}
+
 impl EditableTable for EditableTableState {
     fn toggle_mode(&mut self, markups: &mut HashMap<String, Markup>, active_idx: usize) {
         self.mode = match self.mode {
@@ -117,6 +143,74 @@ impl EditableTable for EditableTableState {
     }
 }
 
+pub struct LoadoutEditorState {
+    pub draft: Option<Loadout>,
+    pub field_state: TableState,
+    pub field_input: EditableTableState,
+    pub error: Option<String>,
+}
+
+impl LoadoutEditorState {
+    pub fn open(&mut self, loadout: &Loadout) {
+        self.draft = Some(loadout.clone());
+        self.field_state.select(Some(0));
+        self.field_input.close();
+        self.error = None;
+    }
+
+    pub fn close(&mut self) {
+        self.draft = None;
+        self.field_input.close();
+        self.error = None;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.draft.is_some()
+    }
+
+    pub fn selected_field(&self) -> Option<LoadoutField> {
+        self.field_state
+            .selected()
+            .and_then(|idx| LoadoutField::ALL.get(idx).copied())
+    }
+
+    pub fn next_field(&mut self) {
+        let i = match self.field_state.selected() {
+            Some(i) if i < LoadoutField::ALL.len() => i + 1,
+            _ => 0,
+        };
+        self.field_state.select(Some(i));
+    }
+
+    pub fn previous_field(&mut self) {
+        let i = match self.field_state.selected() {
+            Some(0) | None => LoadoutField::ALL.len(),
+            Some(i) => i - 1,
+        };
+        self.field_state.select(Some(i));
+    }
+
+    pub fn edit_field(&mut self) {
+        if let (Some(field), Some(draft)) = (self.selected_field(), &self.draft) {
+            self.field_input.input = field.value(draft);
+            self.field_input.mode = EditableTableMode::Edit;
+            self.error = None;
+        }
+    }
+
+    pub fn commit_field(&mut self) {
+        if let (Some(field), Some(draft)) = (self.selected_field(), self.draft.as_mut()) {
+            match field.set(draft, &self.field_input.input) {
+                Ok(()) => {
+                    self.field_input.close();
+                    self.error = None;
+                }
+                Err(error) => self.error = Some(error),
+            }
+        }
+    }
+}
+
 pub trait SectionState {
     fn next(&mut self);
     fn previous(&mut self);
@@ -128,10 +222,13 @@ pub trait SectionState {
 pub struct HomeSectionState {
     pub active_section: HomeSection,
     pub skills_scroll_offset: u16,
+    pub activity_scroll_offset: u16,
     pub loot_summary_scroll_offset: u16,
+    pub loot_packs_scroll_offset: u16,
     pub loot_details_scroll_offset: u16,
     pub combat_self_scroll_offset: u16,
     pub combat_target_scroll_offset: u16,
+    pub pvp_scroll_offset: u16,
 }
 
 impl SectionState for HomeSectionState {
@@ -167,10 +264,13 @@ impl SectionState for HomeSectionState {
         self.active_section = match value {
             0 => HomeSection::None,
             1 => HomeSection::Skills,
-            2 => HomeSection::LootSummary,
-            3 => HomeSection::LootDetails,
-            4 => HomeSection::CombatSelf,
-            5 => HomeSection::CombatTarget,
+            2 => HomeSection::Activity,
+            3 => HomeSection::LootSummary,
+            4 => HomeSection::LootPacks,
+            5 => HomeSection::LootDetails,
+            6 => HomeSection::CombatSelf,
+            7 => HomeSection::CombatTarget,
+            8 => HomeSection::Pvp,
             _ => HomeSection::None,
         };
     }
@@ -181,11 +281,17 @@ impl SectionState for HomeSectionState {
                     self.skills_scroll_offset -= 1;
                 }
             }
+            HomeSection::Activity if self.activity_scroll_offset > 0 => {
+                self.activity_scroll_offset -= 1;
+            }
             HomeSection::LootSummary => {
                 if self.loot_summary_scroll_offset > 0 {
                     self.loot_summary_scroll_offset -= 1;
                 }
             }
+            HomeSection::LootPacks if self.loot_packs_scroll_offset > 0 => {
+                self.loot_packs_scroll_offset -= 1;
+            }
             HomeSection::LootDetails => {
                 if self.loot_details_scroll_offset > 0 {
                     self.loot_details_scroll_offset -= 1;
@@ -201,6 +307,9 @@ impl SectionState for HomeSectionState {
                     self.combat_target_scroll_offset -= 1;
                 }
             }
+            HomeSection::Pvp if self.pvp_scroll_offset > 0 => {
+                self.pvp_scroll_offset -= 1;
+            }
             _ => {}
         }
     }
@@ -209,9 +318,15 @@ impl SectionState for HomeSectionState {
             HomeSection::Skills => {
                 self.skills_scroll_offset += 1;
             }
+            HomeSection::Activity => {
+                self.activity_scroll_offset += 1;
+            }
             HomeSection::LootSummary => {
                 self.loot_summary_scroll_offset += 1;
             }
+            HomeSection::LootPacks => {
+                self.loot_packs_scroll_offset += 1;
+            }
             HomeSection::LootDetails => {
                 self.loot_details_scroll_offset += 1;
             }
@@ -221,11 +336,36 @@ impl SectionState for HomeSectionState {
             HomeSection::CombatTarget => {
                 self.combat_target_scroll_offset += 1;
             }
+            HomeSection::Pvp => {
+                self.pvp_scroll_offset += 1;
+            }
             _ => {}
         }
     }
 }
 
+fn clamp_selection(selected: Option<usize>, len: usize) -> Option<usize> {
+    match selected {
+        Some(_) if len == 0 => None,
+        Some(idx) => Some(idx.min(len - 1)),
+        None => None,
+    }
+}
+
+pub enum DeleteTarget {
+    Session(String),
+    Loadout { id: String, name: String },
+}
+
+impl DeleteTarget {
+    pub fn description(&self) -> String {
+        match self {
+            DeleteTarget::Session(name) => format!("session {}", name),
+            DeleteTarget::Loadout { name, .. } => format!("loadout {}", name),
+        }
+    }
+}
+
 pub struct TrackerUI {
     menu_items: Vec<String>,
 
@@ -237,6 +377,21 @@ pub struct TrackerUI {
     pub loadout_table_state: TableState,
     pub markup_table_state: TableState,
     pub markup_editable_table_state: EditableTableState,
+    pub session_replay_state: EditableTableState,
+    pub globals_table_state: TableState,
+    pub globals_filter_state: EditableTableState,
+    pub globals_filter: String,
+    globals_view: Vec<usize>,
+    globals_view_source: Option<(String, usize)>,
+    pub blueprint_table_state: TableState,
+    pub blueprint_input_state: EditableTableState,
+    pub loadout_editor_state: LoadoutEditorState,
+    pub pending_delete: Option<DeleteTarget>,
+    pub pending_recompute: Option<String>,
+    pub show_loadout_analytics: bool,
+    // The first marked session is the baseline
+    pub compared_sessions: Vec<String>,
+    pub show_session_comparison: bool,
 }
 
 impl TrackerUI {
@@ -249,16 +404,21 @@ impl TrackerUI {
             home_section_state: HomeSectionState {
                 active_section: HomeSection::None,
                 skills_scroll_offset: 0,
+                activity_scroll_offset: 0,
                 loot_summary_scroll_offset: 0,
+                loot_packs_scroll_offset: 0,
                 loot_details_scroll_offset: 0,
                 combat_self_scroll_offset: 0,
                 combat_target_scroll_offset: 0,
+                pvp_scroll_offset: 0,
             },
             active_menu_item: MenuItem::Home,
-            menu_items: vec!["Home", "Session", "Loadout", "Markup", "Options", "Quit"]
-                .iter()
-                .map(|s| s.to_string())
-                .collect(),
+            menu_items: vec![
+                "Home", "Session", "Loadout", "Markup", "Globals", "Crafting", "Options", "Quit",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
             active_session_idx,
             active_loadout_idx,
             session_list_state,
@@ -268,8 +428,94 @@ impl TrackerUI {
                 mode: EditableTableMode::View,
                 input: String::new(),
             },
+            session_replay_state: EditableTableState {
+                mode: EditableTableMode::View,
+                input: String::new(),
+            },
+            globals_table_state: TableState::default(),
+            globals_filter_state: EditableTableState {
+                mode: EditableTableMode::View,
+                input: String::new(),
+            },
+            globals_filter: String::new(),
+            globals_view: vec![],
+            globals_view_source: None,
+            blueprint_table_state: TableState::default(),
+            blueprint_input_state: EditableTableState {
+                mode: EditableTableMode::View,
+                input: String::new(),
+            },
+            loadout_editor_state: LoadoutEditorState {
+                draft: None,
+                field_state: TableState::default(),
+                field_input: EditableTableState {
+                    mode: EditableTableMode::View,
+                    input: String::new(),
+                },
+                error: None,
+            },
+            pending_delete: None,
+            pending_recompute: None,
+            show_loadout_analytics: false,
+            compared_sessions: vec![],
+            show_session_comparison: false,
         };
     }
+    pub fn sync_active(&mut self, tracker: &Tracker) {
+        let mut sessions_vec: Vec<&Session> = tracker.sessions.values().collect();
+        sessions_vec.sort_by_key(|s| Reverse(s.created_at));
+        self.active_session_idx = sessions_vec
+            .iter()
+            .position(|s| s.name == tracker.current_session.name);
+        self.session_list_state.select(clamp_selection(
+            self.session_list_state.selected(),
+            sessions_vec.len(),
+        ));
+        self.compared_sessions
+            .retain(|name| sessions_vec.iter().any(|s| s.name == *name));
+        if self.compared_sessions.len() < 2 {
+            self.show_session_comparison = false;
+        }
+
+        let mut loadouts_vec: Vec<&Loadout> = tracker.loadouts.values().collect();
+        loadouts_vec.sort_by_key(|l| Reverse(l.created_at));
+        self.active_loadout_idx = loadouts_vec
+            .iter()
+            .position(|l| l.id == tracker.current_session.loadout.id);
+        self.loadout_table_state.select(clamp_selection(
+            self.loadout_table_state.selected(),
+            loadouts_vec.len(),
+        ));
+    }
+    pub fn selected_session<'a>(&self, tracker: &'a Tracker) -> Option<&'a Session> {
+        let mut sessions_vec: Vec<&Session> = tracker.sessions.values().collect();
+        sessions_vec.sort_by_key(|s| Reverse(s.created_at));
+        self.session_list_state
+            .selected()
+            .and_then(|idx| sessions_vec.get(idx).copied())
+    }
+    pub fn toggle_compared_session(&mut self, session_name: &str) {
+        match self
+            .compared_sessions
+            .iter()
+            .position(|name| name == session_name)
+        {
+            Some(idx) => {
+                self.compared_sessions.remove(idx);
+            }
+            None => self.compared_sessions.push(session_name.to_string()),
+        }
+        if self.compared_sessions.len() < 2 {
+            self.show_session_comparison = false;
+        }
+    }
+    pub fn selected_loadout<'a>(&self, tracker: &'a Tracker) -> Option<&'a Loadout> {
+        let mut loadouts_vec: Vec<&Loadout> = tracker.loadouts.values().collect();
+        loadouts_vec.sort_by_key(|l| Reverse(l.created_at));
+        self.loadout_table_state
+            .selected()
+            .and_then(|idx| loadouts_vec.get(idx).copied())
+    }
     pub fn next_session(&mut self, items: Vec<&Session>) {
         if items.len() == 0 {
             return;
@@ -366,6 +612,76 @@ impl TrackerUI {
         };
         self.markup_table_state.select(Some(i));
     }
+    pub fn refresh_globals_view(&mut self, tracker: &Tracker) {
+        let source = (self.globals_filter.clone(), tracker.globals.records().len());
+        if self.globals_view_source.as_ref() == Some(&source) {
+            return;
+        }
+        let filter = self.globals_filter.to_lowercase();
+        self.globals_view = tracker
+            .globals
+            .records()
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, record)| record.matches(&filter))
+            .map(|(idx, _)| idx)
+            .collect();
+        self.globals_view_source = Some(source);
+    }
+    pub fn filtered_globals<'a>(&self, tracker: &'a Tracker) -> Vec<&'a GlobalRecord> {
+        let records = tracker.globals.records();
+        self.globals_view
+            .iter()
+            .filter_map(|idx| records.get(*idx))
+            .collect()
+    }
+    pub fn selected_blueprint<'a>(&self, tracker: &'a Tracker) -> Option<&'a String> {
+        let blueprint_names = tracker.current_session.blueprint_names();
+        self.blueprint_table_state
+            .selected()
+            .and_then(|idx| blueprint_names.get(idx).copied())
+    }
+    pub fn next_blueprint(&mut self, items: Vec<&String>) {
+        if items.is_empty() {
+            return;
+        }
+        let i = match self.blueprint_table_state.selected() {
+            Some(i) if i < items.len() - 1 => i + 1,
+            _ => 0,
+        };
+        self.blueprint_table_state.select(Some(i));
+    }
+    pub fn previous_blueprint(&mut self, items: Vec<&String>) {
+        if items.is_empty() {
+            return;
+        }
+        let i = match self.blueprint_table_state.selected() {
+            Some(0) | None => items.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.blueprint_table_state.select(Some(i));
+    }
+    pub fn next_global(&mut self, items: Vec<&GlobalRecord>) {
+        if items.is_empty() {
+            return;
+        }
+        let i = match self.globals_table_state.selected() {
+            Some(i) if i < items.len() - 1 => i + 1,
+            _ => 0,
+        };
+        self.globals_table_state.select(Some(i));
+    }
+    pub fn previous_global(&mut self, items: Vec<&GlobalRecord>) {
+        if items.is_empty() {
+            return;
+        }
+        let i = match self.globals_table_state.selected() {
+            Some(0) | None => items.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.globals_table_state.select(Some(i));
+    }
 }
 
 pub trait UI {
@@ -415,6 +731,32 @@ impl UI for TrackerUI {
                     tracker,
                     self.home_section_state.skills_scroll_offset,
                 );
+                let activity_color = match self.home_section_state.active_section {
+                    HomeSection::Activity => Color::Yellow,
+                    _ => ui_color,
+                };
+                let activity_section = match tracker.current_session.activity {
+                    Activity::Hunting => TrackerUI::get_mobs_section(
+                        activity_color,
+                        tracker,
+                        self.home_section_state.activity_scroll_offset,
+                    ),
+                    Activity::Mining => TrackerUI::get_mining_section(
+                        activity_color,
+                        tracker,
+                        self.home_section_state.activity_scroll_offset,
+                    ),
+                    Activity::Crafting => TrackerUI::get_crafting_summary_section(
+                        activity_color,
+                        tracker,
+                        self.home_section_state.activity_scroll_offset,
+                    ),
+                };
+
+                let skills_body_chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                    .split(body_chunks[0]);
                 let loot_summary_section = TrackerUI::get_summary_loot_section(
                     match self.home_section_state.active_section {
                         HomeSection::LootSummary => Color::Yellow,
@@ -423,6 +765,14 @@ impl UI for TrackerUI {
                     tracker,
                     self.home_section_state.loot_summary_scroll_offset,
                 );
+                let loot_packs_section = TrackerUI::get_loot_packs_section(
+                    match self.home_section_state.active_section {
+                        HomeSection::LootPacks => Color::Yellow,
+                        _ => ui_color,
+                    },
+                    tracker,
+                    self.home_section_state.loot_packs_scroll_offset,
+                );
                 let loot_details_section = TrackerUI::get_details_loot_section(
                     match self.home_section_state.active_section {
                         HomeSection::LootDetails => Color::Yellow,
@@ -434,12 +784,26 @@ impl UI for TrackerUI {
 
                 let loot_body_chunks = Layout::default()
                     .direction(Direction::Vertical)
-                    .constraints([Constraint::Percentage(45), Constraint::Percentage(55)].as_ref())
+                    .constraints(
+                        [
+                            Constraint::Percentage(30),
+                            Constraint::Percentage(35),
+                            Constraint::Percentage(35),
+                        ]
+                        .as_ref(),
+                    )
                     .split(body_chunks[1]);
 
                 let combat_body_chunks = Layout::default()
                     .direction(Direction::Vertical)
-                    .constraints([Constraint::Percentage(70), Constraint::Percentage(30)].as_ref())
+                    .constraints(
+                        [
+                            Constraint::Percentage(45),
+                            Constraint::Percentage(25),
+                            Constraint::Percentage(30),
+                        ]
+                        .as_ref(),
+                    )
                     .split(body_chunks[2]);
 
                 let self_combat_section = TrackerUI::get_self_combat_section(
@@ -458,12 +822,39 @@ impl UI for TrackerUI {
                     tracker,
                     self.home_section_state.combat_target_scroll_offset,
                 );
+                let pvp_section = TrackerUI::get_pvp_section(
+                    match self.home_section_state.active_section {
+                        HomeSection::Pvp => Color::Yellow,
+                        _ => ui_color,
+                    },
+                    tracker,
+                    self.home_section_state.pvp_scroll_offset,
+                );
 
-                f.render_widget(skills_section, body_chunks[0]);
+                f.render_widget(skills_section, skills_body_chunks[0]);
+                f.render_widget(activity_section, skills_body_chunks[1]);
                 f.render_widget(loot_summary_section, loot_body_chunks[0]);
-                f.render_widget(loot_details_section, loot_body_chunks[1]);
+                f.render_widget(loot_packs_section, loot_body_chunks[1]);
+                f.render_widget(loot_details_section, loot_body_chunks[2]);
                 f.render_widget(self_combat_section, combat_body_chunks[0]);
                 f.render_widget(target_combat_section, combat_body_chunks[1]);
+                f.render_widget(pvp_section, combat_body_chunks[2]);
+            }
+            MenuItem::Session if self.show_session_comparison => {
+                let column_width = 80 / self.compared_sessions.len().max(1) as u16;
+                let mut widths = vec![Constraint::Percentage(20)];
+                widths.extend(
+                    self.compared_sessions
+                        .iter()
+                        .map(|_| Constraint::Percentage(column_width)),
+                );
+                let session_comparison_section = TrackerUI::get_session_comparison_section(
+                    ui_color,
+                    tracker,
+                    &self.compared_sessions,
+                    &widths,
+                );
+                f.render_widget(session_comparison_section, chunks[1]);
             }
             MenuItem::Session => {
                 let body_chunks = Layout::default()
@@ -474,33 +865,76 @@ impl UI for TrackerUI {
                 let session_list_section = TrackerUI::get_session_list_section(
                     ui_color,
                     tracker,
-                    self.active_session_idx.unwrap(),
+                    self.active_session_idx,
+                    &self.compared_sessions,
+                );
+                let session_details_section = TrackerUI::get_session_details_section(
+                    ui_color,
+                    tracker,
+                    &self.session_replay_state,
+                    self.selected_session(tracker),
                 );
-                let session_details_section =
-                    TrackerUI::get_session_details_section(ui_color, tracker);
+                let details_chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints(
+                        [
+                            Constraint::Percentage(30),
+                            Constraint::Percentage(35),
+                            Constraint::Percentage(35),
+                        ]
+                        .as_ref(),
+                    )
+                    .split(body_chunks[1]);
+                let breakdown_session = self
+                    .selected_session(tracker)
+                    .and_then(|selected| {
+                        tracker
+                            .live_sessions()
+                            .find(|session| session.name == selected.name)
+                    })
+                    .unwrap_or(&tracker.current_session);
+                let loadout_breakdown_section =
+                    TrackerUI::get_loadout_breakdown_section(ui_color, breakdown_session);
+                let loadout_timeline_section =
+                    TrackerUI::get_loadout_timeline_section(ui_color, breakdown_session);
 
                 f.render_stateful_widget(
                     session_list_section,
                     body_chunks[0],
                     &mut self.session_list_state,
                 );
-                f.render_widget(session_details_section, body_chunks[1]);
+                f.render_widget(session_details_section, details_chunks[0]);
+                f.render_widget(loadout_breakdown_section, details_chunks[1]);
+                f.render_widget(loadout_timeline_section, details_chunks[2]);
+            }
+            MenuItem::Loadout if self.show_loadout_analytics => {
+                let loadout_analytics_section =
+                    TrackerUI::get_loadout_analytics_section(ui_color, tracker);
+                f.render_widget(loadout_analytics_section, chunks[1]);
             }
             MenuItem::Loadout => {
+                let constraints = match self.loadout_editor_state.is_open() {
+                    true => vec![Constraint::Percentage(65), Constraint::Percentage(35)],
+                    false => vec![Constraint::Percentage(100)],
+                };
                 let body_chunks = Layout::default()
                     .direction(Direction::Horizontal)
-                    .constraints([Constraint::Percentage(100)].as_ref())
+                    .constraints(constraints)
                     .split(chunks[1]);
-                let loadouts_section = TrackerUI::get_loadouts_section(
-                    ui_color,
-                    tracker,
-                    self.active_loadout_idx.unwrap(),
-                );
+                let loadouts_section =
+                    TrackerUI::get_loadouts_section(ui_color, tracker, self.active_loadout_idx);
                 f.render_stateful_widget(
                     loadouts_section,
                     body_chunks[0],
                     &mut self.loadout_table_state,
                 );
+                if self.loadout_editor_state.is_open() {
+                    let mut field_state = self.loadout_editor_state.field_state.clone();
+                    let editor_section =
+                        TrackerUI::get_loadout_editor_section(ui_color, &self.loadout_editor_state);
+                    f.render_stateful_widget(editor_section, body_chunks[1], &mut field_state);
+                    self.loadout_editor_state.field_state = field_state;
+                }
             }
             MenuItem::Markup => {
                 let body_chunks = Layout::default()
@@ -519,6 +953,53 @@ impl UI for TrackerUI {
                     &mut self.markup_table_state,
                 );
             }
+            MenuItem::Globals => {
+                let body_chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+                    .split(chunks[1]);
+                self.refresh_globals_view(tracker);
+                let globals_vec = self.filtered_globals(tracker);
+                let globals_section = TrackerUI::get_globals_section(
+                    ui_color,
+                    globals_vec,
+                    &self.globals_filter_state,
+                    &self.globals_filter,
+                );
+                let side_chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Percentage(70), Constraint::Percentage(30)].as_ref())
+                    .split(body_chunks[1]);
+                let global_rates_section = TrackerUI::get_global_rates_section(ui_color, tracker);
+                let global_categories_section =
+                    TrackerUI::get_global_categories_section(ui_color, tracker);
+                f.render_stateful_widget(
+                    globals_section,
+                    body_chunks[0],
+                    &mut self.globals_table_state,
+                );
+                f.render_widget(global_rates_section, side_chunks[0]);
+                f.render_widget(global_categories_section, side_chunks[1]);
+            }
+            MenuItem::Crafting => {
+                let body_chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(70), Constraint::Percentage(30)].as_ref())
+                    .split(chunks[1]);
+                let blueprints_section = TrackerUI::get_blueprints_section(ui_color, tracker);
+                let blueprint_details_section = TrackerUI::get_blueprint_details_section(
+                    ui_color,
+                    tracker,
+                    &self.blueprint_input_state,
+                    self.selected_blueprint(tracker),
+                );
+                f.render_stateful_widget(
+                    blueprints_section,
+                    body_chunks[0],
+                    &mut self.blueprint_table_state,
+                );
+                f.render_widget(blueprint_details_section, body_chunks[1]);
+            }
             // MenuItem::Options => {
             //     let options_section = draw_options_section(tracker);
             //     f.render_widget(options_section, chunks[1]);
@@ -528,13 +1009,54 @@ impl UI for TrackerUI {
 
         let logs_section = TrackerUI::get_logs_section(ui_color, tracker);
         f.render_widget(logs_section, chunks[2]);
+
+        if let Some(target) = &self.pending_delete {
+            let popup_area = centered_rect(40, 20, f.size());
+            let confirm_section = TrackerUI::get_confirm_delete_section(target);
+            f.render_widget(Clear, popup_area);
+            f.render_widget(confirm_section, popup_area);
+        }
+        if let Some(session_name) = &self.pending_recompute {
+            let popup_area = centered_rect(40, 20, f.size());
+            let confirm_section = TrackerUI::get_confirm_recompute_section(session_name);
+            f.render_widget(Clear, popup_area);
+            f.render_widget(confirm_section, popup_area);
+        }
     }
 }
 
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage((100 - percent_y) / 2),
+                Constraint::Percentage(percent_y),
+                Constraint::Percentage((100 - percent_y) / 2),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage((100 - percent_x) / 2),
+                Constraint::Percentage(percent_x),
+                Constraint::Percentage((100 - percent_x) / 2),
+            ]
+            .as_ref(),
+        )
+        .split(vertical_chunks[1])[1]
+}
+
 pub trait Section {
     // COMMON
     fn get_menu_section<'a>(&'a self, ui_color: Color, active_menu_item: MenuItem) -> Tabs<'a>;
     fn get_logs_section<'a>(ui_color: Color, tracker: &'a Tracker) -> List<'a>;
+    fn get_confirm_delete_section<'a>(target: &'a DeleteTarget) -> Paragraph<'a>;
+    fn get_confirm_recompute_section<'a>(session_name: &'a str) -> Paragraph<'a>;
 
     // HOME
     fn get_skills_section<'a>(
@@ -542,11 +1064,31 @@ pub trait Section {
         tracker: &'a Tracker,
         scroll_offset: u16,
     ) -> Paragraph<'a>;
+    fn get_mobs_section<'a>(
+        ui_color: Color,
+        tracker: &'a Tracker,
+        scroll_offset: u16,
+    ) -> Paragraph<'a>;
+    fn get_mining_section<'a>(
+        ui_color: Color,
+        tracker: &'a Tracker,
+        scroll_offset: u16,
+    ) -> Paragraph<'a>;
+    fn get_crafting_summary_section<'a>(
+        ui_color: Color,
+        tracker: &'a Tracker,
+        scroll_offset: u16,
+    ) -> Paragraph<'a>;
     fn get_summary_loot_section<'a>(
         ui_color: Color,
         tracker: &'a Tracker,
         scroll_offset: u16,
     ) -> Paragraph<'a>;
+    fn get_loot_packs_section<'a>(
+        ui_color: Color,
+        tracker: &'a Tracker,
+        scroll_offset: u16,
+    ) -> Paragraph<'a>;
     fn get_details_loot_section<'a>(
         ui_color: Color,
         tracker: &'a Tracker,
@@ -562,21 +1104,45 @@ pub trait Section {
         tracker: &'a Tracker,
         scroll_offset: u16,
     ) -> Paragraph<'a>;
+    fn get_pvp_section<'a>(
+        ui_color: Color,
+        tracker: &'a Tracker,
+        scroll_offset: u16,
+    ) -> Paragraph<'a>;
 
     // SESSION
     fn get_session_list_section<'a>(
         ui_color: Color,
         tracker: &'a Tracker,
-        active_session_idx: usize,
+        active_session_idx: Option<usize>,
+        compared_sessions: &'a [String],
     ) -> List<'a>;
-    fn get_session_details_section<'a>(ui_color: Color, tracker: &'a Tracker) -> Paragraph<'a>;
+    fn get_session_details_section<'a>(
+        ui_color: Color,
+        tracker: &'a Tracker,
+        replay_state: &'a EditableTableState,
+        selected_session: Option<&'a Session>,
+    ) -> Paragraph<'a>;
+    fn get_loadout_breakdown_section<'a>(ui_color: Color, session: &'a Session) -> Table<'a>;
+    fn get_loadout_timeline_section<'a>(ui_color: Color, session: &'a Session) -> Table<'a>;
+    fn get_session_comparison_section<'a>(
+        ui_color: Color,
+        tracker: &'a Tracker,
+        compared_sessions: &'a [String],
+        widths: &'a [Constraint],
+    ) -> Table<'a>;
 
     // LOADOUT
     fn get_loadouts_section<'a>(
         ui_color: Color,
         tracker: &'a Tracker,
-        active_loadout_idx: usize,
+        active_loadout_idx: Option<usize>,
+    ) -> Table<'a>;
+    fn get_loadout_editor_section<'a>(
+        ui_color: Color,
+        editor_state: &'a LoadoutEditorState,
     ) -> Table<'a>;
+    fn get_loadout_analytics_section<'a>(ui_color: Color, tracker: &'a Tracker) -> Table<'a>;
 
     // MARKUP
     fn get_markups_section<'a>(
@@ -585,6 +1151,25 @@ pub trait Section {
         state: &'a mut EditableTableState,
         active_idx: usize,
     ) -> Table<'a>;
+
+    // GLOBALS
+    fn get_globals_section<'a>(
+        ui_color: Color,
+        globals: Vec<&'a GlobalRecord>,
+        filter_state: &'a EditableTableState,
+        filter: &'a str,
+    ) -> Table<'a>;
+    fn get_global_rates_section<'a>(ui_color: Color, tracker: &'a Tracker) -> Table<'a>;
+    fn get_global_categories_section<'a>(ui_color: Color, tracker: &'a Tracker) -> Paragraph<'a>;
+
+    // CRAFTING
+    fn get_blueprints_section<'a>(ui_color: Color, tracker: &'a Tracker) -> Table<'a>;
+    fn get_blueprint_details_section<'a>(
+        ui_color: Color,
+        tracker: &'a Tracker,
+        input_state: &'a EditableTableState,
+        selected_blueprint: Option<&'a String>,
+    ) -> Paragraph<'a>;
 }
 
 impl Section for TrackerUI {
@@ -638,6 +1223,60 @@ impl Section for TrackerUI {
         return list;
     }
 
+    fn get_confirm_delete_section<'a>(target: &'a DeleteTarget) -> Paragraph<'a> {
+        let paragraph_vec = vec![
+            Spans::from(Span::raw("")),
+            Spans::from(Span::raw(format!("Delete {}?", target.description()))),
+            Spans::from(Span::raw("This cannot be undone.")),
+            Spans::from(Span::raw("")),
+            Spans::from(Span::styled(
+                "y to delete, any other key to cancel",
+                Style::default().fg(Color::Yellow),
+            )),
+        ];
+
+        let paragraph = Paragraph::new(paragraph_vec)
+            .block(
+                Block::default()
+                    .title("Confirm Delete")
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(Color::Red)),
+            )
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(Color::White));
+
+        paragraph
+    }
+
+    fn get_confirm_recompute_section<'a>(session_name: &'a str) -> Paragraph<'a> {
+        let paragraph_vec = vec![
+            Spans::from(Span::raw("")),
+            Spans::from(Span::raw(format!("Recompute {}?", session_name))),
+            Spans::from(Span::raw(
+                "Its stats are rebuilt from the journal with the current loadouts.",
+            )),
+            Spans::from(Span::raw("")),
+            Spans::from(Span::styled(
+                "y to recompute, any other key to cancel",
+                Style::default().fg(Color::Yellow),
+            )),
+        ];
+
+        let paragraph = Paragraph::new(paragraph_vec)
+            .block(
+                Block::default()
+                    .title("Confirm Recompute")
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(Color::Red)),
+            )
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(Color::White));
+
+        paragraph
+    }
+
     // HOME
     fn get_skills_section<'a>(
         ui_color: Color,
@@ -677,33 +1316,187 @@ impl Section for TrackerUI {
         return paragraph;
     }
 
-    fn get_summary_loot_section<'a>(
+    fn get_mobs_section<'a>(
         ui_color: Color,
         tracker: &'a Tracker,
         scroll_offset: u16,
     ) -> Paragraph<'a> {
-        let total_cost = Spans::from(Span::raw(format!(
-            "Total Cost: {} PED",
-            tracker.current_session.stats.total_cost.trunc_with_scale(4),
-        )));
-        let mu_profit_value: Decimal = tracker
-            .current_session
-            .loot_map
-            .values()
-            .collect::<Vec<&SessionLoot>>()
+        let mut sorted_mobs_vec: Vec<&SessionMob> =
+            tracker.current_session.mob_map.values().collect();
+        sorted_mobs_vec.sort_by_key(|mob| Reverse(mob.kills));
+        let mob_items: Vec<Spans> = sorted_mobs_vec
             .iter()
-            .fold(Decimal::new(0, 0), |a, l| {
-                a + (l.tt_value * tracker.markups.get(l.name.as_str()).unwrap().value)
-            });
-        let mu_profit = Spans::from(Span::raw(format!(
-            "MU Profit: {} PED ({}%)",
-            (mu_profit_value - tracker.current_session.stats.total_cost).trunc_with_scale(4),
-            Utils::get_percentage(mu_profit_value, tracker.current_session.stats.total_cost)
-        )));
-        let ped_per_hour = Spans::from(Span::raw(format!(
-            "PED/Hour: {} PED",
-            mu_profit_value
-                .checked_div(Decimal::from(
+            .map(|mob| {
+                Spans::from(Span::raw(format!(
+                    "{} (x{}): {} / {} PED ({}%)",
+                    mob.name,
+                    mob.kills,
+                    mob.tt_value.trunc_with_scale(4),
+                    mob.total_cost.trunc_with_scale(4),
+                    Utils::get_percentage(mob.tt_value, mob.total_cost)
+                )))
+            })
+            .collect();
+
+        let paragraph = Paragraph::new(mob_items)
+            .block(
+                Block::default()
+                    .title("Mobs")
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(ui_color)),
+            )
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .scroll((scroll_offset, 0))
+            .style(Style::default().fg(Color::White));
+
+        paragraph
+    }
+
+    fn get_mining_section<'a>(
+        ui_color: Color,
+        tracker: &'a Tracker,
+        scroll_offset: u16,
+    ) -> Paragraph<'a> {
+        let session = &tracker.current_session;
+        let mining = &session.stats.mining;
+        let drops = Spans::from(Span::raw(format!(
+            "Drops: {} / Claims: {}",
+            mining.drop_count, mining.claim_count
+        )));
+        let find_rate = Spans::from(Span::raw(format!("Find Rate: {}%", mining.find_rate())));
+        let cost_per_claim = Spans::from(Span::raw(format!(
+            "Cost/Claim: {} PED",
+            mining.cost_per_claim().trunc_with_scale(4)
+        )));
+        let return_rate = Spans::from(Span::raw(format!(
+            "Return: {} / {} PED ({}%)",
+            mining.loot_value.trunc_with_scale(4),
+            mining.cost().trunc_with_scale(4),
+            mining.return_percentage()
+        )));
+        let mut spans_vec = vec![drops, find_rate, cost_per_claim, return_rate];
+
+        let mut sorted_sizes_vec: Vec<(&String, &usize)> = mining.claim_size_map.iter().collect();
+        sorted_sizes_vec.sort_by(|a, b| b.1.cmp(a.1));
+        let mut sorted_resources_vec: Vec<(&String, &usize)> = mining.resource_map.iter().collect();
+        sorted_resources_vec.sort_by(|a, b| b.1.cmp(a.1));
+        spans_vec.push(Spans::from(Span::raw("")));
+        spans_vec.push(Spans::from(Span::styled(
+            "Sizes",
+            Style::default().fg(Color::Yellow),
+        )));
+        for (size, count) in sorted_sizes_vec {
+            spans_vec.push(Spans::from(Span::raw(format!("{}: {}", size, count))));
+        }
+        spans_vec.push(Spans::from(Span::raw("")));
+        spans_vec.push(Spans::from(Span::styled(
+            "Resources",
+            Style::default().fg(Color::Yellow),
+        )));
+        for (resource, count) in sorted_resources_vec {
+            spans_vec.push(Spans::from(Span::raw(format!("{}: {}", resource, count))));
+        }
+
+        let paragraph = Paragraph::new(spans_vec)
+            .block(
+                Block::default()
+                    .title("Mining")
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(ui_color)),
+            )
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .scroll((scroll_offset, 0))
+            .style(Style::default().fg(Color::White));
+
+        paragraph
+    }
+
+    fn get_crafting_summary_section<'a>(
+        ui_color: Color,
+        tracker: &'a Tracker,
+        scroll_offset: u16,
+    ) -> Paragraph<'a> {
+        let session = &tracker.current_session;
+        let blueprint_stats: Vec<&BlueprintStats> = session.stats.blueprint_map.values().collect();
+        let attempt_count: usize = blueprint_stats.iter().map(|b| b.attempt_count).sum();
+        let success_count: usize = blueprint_stats.iter().map(|b| b.success_count).sum();
+        let input_cost: Decimal = blueprint_stats
+            .iter()
+            .map(|b| b.input_cost(&tracker.markups))
+            .sum();
+        let output_value: Decimal = blueprint_stats
+            .iter()
+            .map(|b| b.output_value(&tracker.markups))
+            .sum();
+
+        let spans_vec = vec![
+            Spans::from(Span::raw(format!(
+                "Blueprint: {}",
+                session.active_blueprint.as_deref().unwrap_or("None")
+            ))),
+            Spans::from(Span::raw(format!(
+                "Clicks: {} / Successes: {}",
+                attempt_count, success_count
+            ))),
+            Spans::from(Span::raw(format!(
+                "Success Rate: {}%",
+                Utils::get_percentage(Decimal::from(success_count), Decimal::from(attempt_count))
+            ))),
+            Spans::from(Span::raw(format!(
+                "Input Cost: {} PED",
+                input_cost.trunc_with_scale(4)
+            ))),
+            Spans::from(Span::raw(format!(
+                "Output Value: {} PED ({}%)",
+                output_value.trunc_with_scale(4),
+                Utils::get_percentage(output_value, input_cost)
+            ))),
+        ];
+
+        let paragraph = Paragraph::new(spans_vec)
+            .block(
+                Block::default()
+                    .title("Crafting")
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(ui_color)),
+            )
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .scroll((scroll_offset, 0))
+            .style(Style::default().fg(Color::White));
+
+        paragraph
+    }
+
+    fn get_summary_loot_section<'a>(
+        ui_color: Color,
+        tracker: &'a Tracker,
+        scroll_offset: u16,
+    ) -> Paragraph<'a> {
+        let total_cost = Spans::from(Span::raw(format!(
+            "Total Cost: {} PED",
+            tracker.current_session.stats.total_cost.trunc_with_scale(4),
+        )));
+        let mu_profit_value: Decimal = tracker
+            .current_session
+            .loot_map
+            .values()
+            .collect::<Vec<&SessionLoot>>()
+            .iter()
+            .fold(Decimal::new(0, 0), |a, l| {
+                a + (l.tt_value * tracker.markups.get(l.name.as_str()).unwrap().value)
+            });
+        let mu_profit = Spans::from(Span::raw(format!(
+            "MU Profit: {} PED ({}%)",
+            (mu_profit_value - tracker.current_session.stats.total_cost).trunc_with_scale(4),
+            Utils::get_percentage(mu_profit_value, tracker.current_session.stats.total_cost)
+        )));
+        let ped_per_hour = Spans::from(Span::raw(format!(
+            "PED/Hour: {} PED",
+            mu_profit_value
+                .checked_div(Decimal::from(
                     tracker.current_session.elapsed().num_seconds()
                 ))
                 .unwrap_or(Decimal::ZERO)
@@ -732,10 +1525,20 @@ impl Section for TrackerUI {
                 tracker.current_session.stats.total_cost
             )
         )));
+        let heal_cost = Spans::from(Span::raw(format!(
+            "Heal Cost: {} PED",
+            tracker.current_session.stats.heal_cost.trunc_with_scale(4),
+        )));
+        let armor_cost = Spans::from(Span::raw(format!(
+            "Armor Cost: {} PED",
+            tracker.current_session.stats.armor_cost.trunc_with_scale(4),
+        )));
         let spans_vec = vec![
             ped_per_hour,
             cost_per_hour,
             total_cost,
+            heal_cost,
+            armor_cost,
             mu_profit,
             tt_profit,
         ];
@@ -755,6 +1558,80 @@ impl Section for TrackerUI {
         return paragraph;
     }
 
+    fn get_loot_packs_section<'a>(
+        ui_color: Color,
+        tracker: &'a Tracker,
+        scroll_offset: u16,
+    ) -> Paragraph<'a> {
+        let pack_stats = tracker.current_session.loot_pack_stats();
+        let pack_count = Decimal::from(pack_stats.pack_count);
+
+        let packs = Spans::from(Span::raw(format!("Packs: {}", pack_stats.pack_count)));
+        let average_tt = Spans::from(Span::raw(format!(
+            "Avg Pack TT: {} PED",
+            pack_stats
+                .tt_value
+                .checked_div(pack_count)
+                .unwrap_or(Decimal::ZERO)
+                .trunc_with_scale(4)
+        )));
+        let average_cost = Spans::from(Span::raw(format!(
+            "Avg Pack Cost: {} PED",
+            pack_stats
+                .cost
+                .checked_div(pack_count)
+                .unwrap_or(Decimal::ZERO)
+                .trunc_with_scale(4)
+        )));
+        let last_pack = Spans::from(Span::raw(match &tracker.current_session.last_pack {
+            Some(pack) => format!(
+                "Last Pack: {} / {} PED ({}%)",
+                pack.tt_value.trunc_with_scale(4),
+                pack.cost.trunc_with_scale(4),
+                pack.return_percentage()
+            ),
+            None => String::from("Last Pack: -"),
+        }));
+
+        let bucket_counts = pack_stats.bucket_counts;
+        let max_count = bucket_counts.iter().copied().max().unwrap_or(0).max(1);
+        let mut histogram_items: Vec<Spans> = bucket_counts
+            .iter()
+            .enumerate()
+            .map(|(idx, count)| {
+                let label = match idx {
+                    0 => format!("<{}%", LOOT_PACK_BUCKETS[0]),
+                    idx if idx == LOOT_PACK_BUCKETS.len() => {
+                        format!(">={}%", LOOT_PACK_BUCKETS[idx - 1])
+                    }
+                    idx => format!("{}-{}%", LOOT_PACK_BUCKETS[idx - 1], LOOT_PACK_BUCKETS[idx]),
+                };
+                Spans::from(Span::raw(format!(
+                    "{:>9} {:<width$} {}",
+                    label,
+                    "#".repeat(count * LOOT_PACK_BAR_WIDTH / max_count),
+                    count,
+                    width = LOOT_PACK_BAR_WIDTH
+                )))
+            })
+            .collect();
+        let mut spans_vec = vec![packs, average_tt, average_cost, last_pack];
+        spans_vec.append(&mut histogram_items);
+
+        let paragraph = Paragraph::new(spans_vec)
+            .block(
+                Block::default()
+                    .title("Loot Packs")
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(ui_color)),
+            )
+            .alignment(Alignment::Center)
+            .scroll((scroll_offset, 0))
+            .style(Style::default().fg(Color::White));
+
+        paragraph
+    }
+
     fn get_details_loot_section<'a>(
         ui_color: Color,
         tracker: &'a Tracker,
@@ -805,13 +1682,37 @@ impl Section for TrackerUI {
             tracker.current_session.stats.self_total_damage
         )));
         let self_total_heal = Spans::from(Span::raw(format!(
-            "Total Heal: {}",
-            tracker.current_session.stats.self_total_heal
+            "Total Heal: {} ({} heals)",
+            tracker.current_session.stats.self_total_heal,
+            tracker.current_session.stats.self_heal_count
+        )));
+        let self_heal_per_pec = Spans::from(Span::raw(format!(
+            "Heal/PEC: {}",
+            tracker.current_session.stats.heal_per_pec()
         )));
         let self_total_shots = Spans::from(Span::raw(format!(
             "Total Shots: {}",
             tracker.current_session.stats.self_attack_count
         )));
+        let self_damage_per_pec = Spans::from(Span::raw(format!(
+            "DPP: {}",
+            tracker.current_session.stats.damage_per_pec()
+        )));
+        let self_average_damage = Spans::from(Span::raw(format!(
+            "Avg Damage/Hit: {}",
+            tracker.current_session.stats.average_damage_per_hit()
+        )));
+        let self_crit_damage_share = Spans::from(Span::raw(format!(
+            "Crit Damage Share: {}%",
+            tracker.current_session.stats.crit_damage_share()
+        )));
+        let self_damage_per_second = Spans::from(Span::raw(format!(
+            "DPS: {}",
+            tracker
+                .current_session
+                .stats
+                .damage_per_second(tracker.current_session.elapsed())
+        )));
         let self_crit_chance = Spans::from(Span::raw(format!(
             "Crit Chance: {}%",
             Utils::get_percentage(
@@ -841,7 +1742,12 @@ impl Section for TrackerUI {
         let paragraph: Paragraph = Paragraph::new(vec![
             self_total_shots,
             self_total_damage,
+            self_damage_per_pec,
+            self_average_damage,
+            self_crit_damage_share,
+            self_damage_per_second,
             self_total_heal,
+            self_heal_per_pec,
             self_crit_chance,
             self_miss,
             self_deflected,
@@ -898,11 +1804,91 @@ impl Section for TrackerUI {
         paragraph
     }
 
+    fn get_pvp_section<'a>(
+        ui_color: Color,
+        tracker: &'a Tracker,
+        scroll_offset: u16,
+    ) -> Paragraph<'a> {
+        let stats = &tracker.current_session.stats;
+        let kdr = Spans::from(Span::raw(format!(
+            "KDR: {} ({} / {})",
+            stats.pvp_kdr(),
+            stats.pvp_kill_count,
+            stats.pvp_death_count
+        )));
+        let disables = Spans::from(Span::raw(format!(
+            "Disables: {} / Disabled: {}",
+            stats.pvp_disable_count, stats.pvp_disabled_count
+        )));
+        let mut sorted_opponents_vec: Vec<(&String, &PvpTally)> =
+            stats.pvp_opponent_map.iter().collect();
+        sorted_opponents_vec.sort_by_key(|(_, opponent)| Reverse(opponent.kills + opponent.deaths));
+        let mut opponent_items: Vec<Spans> = sorted_opponents_vec
+            .iter()
+            .map(|(name, tally)| {
+                Spans::from(Span::raw(format!(
+                    "{}: {}K {}D {}Dis {}Disd",
+                    name, tally.kills, tally.deaths, tally.disables, tally.disabled
+                )))
+            })
+            .collect();
+        let mut sorted_own_weapons_vec: Vec<(&String, &PvpTally)> =
+            stats.pvp_own_weapon_map.iter().collect();
+        sorted_own_weapons_vec.sort_by_key(|(_, weapon)| Reverse(weapon.kills + weapon.disables));
+        let mut own_weapon_items: Vec<Spans> = sorted_own_weapons_vec
+            .iter()
+            .map(|(name, tally)| {
+                Spans::from(Span::raw(format!(
+                    "{}: {}K {}Dis",
+                    name, tally.kills, tally.disables
+                )))
+            })
+            .collect();
+        let mut sorted_opponent_weapons_vec: Vec<(&String, &PvpTally)> =
+            stats.pvp_opponent_weapon_map.iter().collect();
+        sorted_opponent_weapons_vec
+            .sort_by_key(|(_, weapon)| Reverse(weapon.deaths + weapon.disabled));
+        let mut opponent_weapon_items: Vec<Spans> = sorted_opponent_weapons_vec
+            .iter()
+            .map(|(name, tally)| {
+                Spans::from(Span::raw(format!(
+                    "{}: {}D {}Disd",
+                    name, tally.deaths, tally.disabled
+                )))
+            })
+            .collect();
+        let mut spans_vec = vec![kdr, disables];
+        spans_vec.append(&mut opponent_items);
+        if !own_weapon_items.is_empty() {
+            spans_vec.push(Spans::from(Span::raw("Our Weapons")));
+            spans_vec.append(&mut own_weapon_items);
+        }
+        if !opponent_weapon_items.is_empty() {
+            spans_vec.push(Spans::from(Span::raw("Their Weapons")));
+            spans_vec.append(&mut opponent_weapon_items);
+        }
+
+        let paragraph: Paragraph = Paragraph::new(spans_vec)
+            .block(
+                Block::default()
+                    .title("PvP")
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(ui_color)),
+            )
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .scroll((scroll_offset, 0))
+            .style(Style::default().fg(Color::White));
+
+        paragraph
+    }
+
     // Session
     fn get_session_list_section<'a>(
         ui_color: Color,
         tracker: &'a Tracker,
-        active_session_idx: usize,
+        active_session_idx: Option<usize>,
+        compared_sessions: &'a [String],
     ) -> List<'a> {
         let mut sessions_vec: Vec<&Session> = tracker.sessions.values().into_iter().collect();
         sessions_vec.sort_by(|a, b| b.created_at.cmp(&a.created_at));
@@ -910,15 +1896,33 @@ impl Section for TrackerUI {
             .iter()
             .enumerate()
             .map(|(idx, s)| {
-                if idx == active_session_idx {
-                    return ListItem::new(s.name.as_str()).style(Style::default().fg(Color::Green));
+                let name = match compared_sessions.contains(&s.name) {
+                    true => format!("[x] {}", s.name),
+                    false => s.name.clone(),
+                };
+                if Some(idx) == active_session_idx {
+                    return ListItem::new(name).style(Style::default().fg(Color::Green));
+                }
+                if s.archived {
+                    return ListItem::new(format!("{} (archived)", name))
+                        .style(Style::default().fg(Color::DarkGray));
                 }
-                ListItem::new(s.name.as_str()).style(Style::default().fg(Color::White))
+                ListItem::new(name).style(Style::default().fg(Color::White))
             })
             .collect();
 
+        let mut title = String::from("Sessions");
+        if tracker.show_archived {
+            title.push_str(" (archived shown)");
+        }
+        if !compared_sessions.is_empty() {
+            title.push_str(&format!(
+                " ({} marked, v to compare)",
+                compared_sessions.len()
+            ));
+        }
         let list: List = List::new(session_items)
-            .block(Block::default().title("Sessions").borders(Borders::ALL))
+            .block(Block::default().title(title).borders(Borders::ALL))
             .style(Style::default().fg(ui_color))
             .highlight_style(
                 Style::default()
@@ -930,7 +1934,12 @@ impl Section for TrackerUI {
         list
     }
 
-    fn get_session_details_section<'a>(ui_color: Color, tracker: &'a Tracker) -> Paragraph<'a> {
+    fn get_session_details_section<'a>(
+        ui_color: Color,
+        tracker: &'a Tracker,
+        replay_state: &'a EditableTableState,
+        selected_session: Option<&'a Session>,
+    ) -> Paragraph<'a> {
         let elapsed_time = Spans::from(Span::raw(format!(
             "Elapsed Time: {}",
             tracker.current_session.pretty_elapsed()
@@ -939,7 +1948,23 @@ impl Section for TrackerUI {
             "Running: {}",
             tracker.current_session.is_active
         )));
-        let paragraph_vec = vec![elapsed_time, is_running];
+        let activity = Spans::from(Span::raw(format!(
+            "Activity: {} (t to change)",
+            tracker.current_session.activity.name()
+        )));
+        let mut paragraph_vec = vec![elapsed_time, is_running, activity];
+        if replay_state.mode == EditableTableMode::Edit {
+            let target = selected_session.unwrap_or(&tracker.current_session);
+            paragraph_vec.push(Spans::from(Span::raw("")));
+            paragraph_vec.push(Spans::from(Span::styled(
+                format!(
+                    "Replay into {} (YYYY-MM-DD HH:MM[:SS]..YYYY-MM-DD HH:MM[:SS]):",
+                    target.name
+                ),
+                Style::default().fg(Color::Yellow),
+            )));
+            paragraph_vec.push(Spans::from(Span::raw(replay_state.input.as_str())));
+        }
 
         let paragraph = Paragraph::new(paragraph_vec)
             .block(
@@ -955,11 +1980,168 @@ impl Section for TrackerUI {
         paragraph
     }
 
+    fn get_loadout_breakdown_section<'a>(ui_color: Color, session: &'a Session) -> Table<'a> {
+        let headers = vec![
+            "Loadout", "Segments", "Shots", "Damage", "Crit %", "Miss %", "Cost", "Loot",
+            "Return %",
+        ];
+        let header = Row::new(headers)
+            .style(Style::default().fg(Color::Yellow))
+            .bottom_margin(1);
+        let rows: Vec<Row> = session
+            .loadout_totals()
+            .into_iter()
+            .map(|total| {
+                let rows_vec = vec![
+                    Cell::from(total.loadout_name.clone()),
+                    Cell::from(total.segment_count.to_string()),
+                    Cell::from(total.attack_count.to_string()),
+                    Cell::from(total.damage.trunc_with_scale(2).to_string()),
+                    Cell::from(format!("{}%", total.crit_rate())),
+                    Cell::from(format!("{}%", total.miss_rate())),
+                    Cell::from(total.cost.trunc_with_scale(4).to_string()),
+                    Cell::from(total.loot_value.trunc_with_scale(4).to_string()),
+                    Cell::from(format!("{}%", total.return_percentage())),
+                ];
+                Row::new(rows_vec).style(Style::default().fg(Color::White))
+            })
+            .collect();
+
+        let table = Table::new(rows)
+            .header(header)
+            .block(
+                Block::default()
+                    .title(format!("Loadouts Used in {}", session.name))
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(ui_color)),
+            )
+            .widths(&[
+                Constraint::Percentage(16),
+                Constraint::Percentage(9),
+                Constraint::Percentage(9),
+                Constraint::Percentage(10),
+                Constraint::Percentage(10),
+                Constraint::Percentage(10),
+                Constraint::Percentage(11),
+                Constraint::Percentage(11),
+                Constraint::Percentage(12),
+            ]);
+
+        table
+    }
+
+    fn get_loadout_timeline_section<'a>(ui_color: Color, session: &'a Session) -> Table<'a> {
+        let headers = vec!["From", "To", "Loadout", "Shots", "Cost", "Loot", "Return %"];
+        let header = Row::new(headers)
+            .style(Style::default().fg(Color::Yellow))
+            .bottom_margin(1);
+        let rows: Vec<Row> = session
+            .loadout_segments
+            .iter()
+            .rev()
+            .map(|segment| {
+                let rows_vec = vec![
+                    Cell::from(segment.started_at.format(TIMESTAMP_FORMAT).to_string()),
+                    Cell::from(segment.ended_at.format(TIMESTAMP_FORMAT).to_string()),
+                    Cell::from(segment.loadout_name.as_str()),
+                    Cell::from(segment.attack_count.to_string()),
+                    Cell::from(segment.cost.trunc_with_scale(4).to_string()),
+                    Cell::from(segment.loot_value.trunc_with_scale(4).to_string()),
+                    Cell::from(format!("{}%", segment.return_percentage())),
+                ];
+                Row::new(rows_vec).style(Style::default().fg(Color::White))
+            })
+            .collect();
+
+        let table = Table::new(rows)
+            .header(header)
+            .block(
+                Block::default()
+                    .title("Loadout Timeline")
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(ui_color)),
+            )
+            .widths(&[
+                Constraint::Percentage(19),
+                Constraint::Percentage(19),
+                Constraint::Percentage(16),
+                Constraint::Percentage(8),
+                Constraint::Percentage(11),
+                Constraint::Percentage(11),
+                Constraint::Percentage(12),
+            ]);
+
+        table
+    }
+
+    fn get_session_comparison_section<'a>(
+        ui_color: Color,
+        tracker: &'a Tracker,
+        compared_sessions: &'a [String],
+        widths: &'a [Constraint],
+    ) -> Table<'a> {
+        let sessions_vec: Vec<&Session> = compared_sessions
+            .iter()
+            .filter_map(|name| tracker.live_sessions().find(|s| s.name == *name))
+            .collect();
+        let comparison = SessionComparison::collect(&sessions_vec, &tracker.markups);
+
+        let mut headers = vec![String::from("Metric")];
+        headers.extend(comparison.session_names.iter().cloned());
+        let header = Row::new(headers)
+            .style(Style::default().fg(Color::Yellow))
+            .bottom_margin(1);
+        let rows: Vec<Row> = comparison
+            .rows
+            .iter()
+            .map(|row| {
+                let mut rows_vec = vec![Cell::from(row.label.clone())];
+                for (idx, value) in row.values.iter().enumerate() {
+                    if idx == 0 {
+                        rows_vec.push(Cell::from(value.to_string()));
+                        continue;
+                    }
+                    let delta = row.delta(idx);
+                    let color = match row.is_improvement(idx) {
+                        Some(true) => Color::Green,
+                        Some(false) => Color::Red,
+                        None => Color::White,
+                    };
+                    let delta_text = match delta.is_sign_negative() {
+                        true => format!(" ({})", delta),
+                        false => format!(" (+{})", delta),
+                    };
+                    rows_vec.push(Cell::from(Spans::from(vec![
+                        Span::raw(value.to_string()),
+                        Span::styled(delta_text, Style::default().fg(color)),
+                    ])));
+                }
+                Row::new(rows_vec).style(Style::default().fg(Color::White))
+            })
+            .collect();
+
+        let title = match comparison.session_names.first() {
+            Some(baseline) => format!("Session Comparison (deltas against {})", baseline),
+            None => String::from("Session Comparison"),
+        };
+        let table = Table::new(rows)
+            .header(header)
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(ui_color)),
+            )
+            .widths(widths);
+
+        table
+    }
+
     // Loadout
     fn get_loadouts_section<'a>(
         ui_color: Color,
         tracker: &'a Tracker,
-        active_loadout_idx: usize,
+        active_loadout_idx: Option<usize>,
     ) -> Table<'a> {
         let headers = vec![
             "Name",
@@ -968,9 +2150,16 @@ impl Section for TrackerUI {
             "Scope",
             "Sight 1",
             "Sight 2",
-            "Decay",
+            "Decay (PEC)",
             "Ammo Burn",
             "Cost per Shot",
+            "DPP",
+            "Finder",
+            "Cost per Drop",
+            "Healing Tool",
+            "Cost per Heal",
+            "Armor",
+            "Armor per Hit",
         ];
 
         let header = Row::new(headers)
@@ -978,6 +2167,11 @@ impl Section for TrackerUI {
             .bottom_margin(1);
         let mut loadouts_vec: Vec<&Loadout> = tracker.loadouts.values().into_iter().collect();
         loadouts_vec.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        let dpp_map: HashMap<String, Decimal> =
+            LoadoutLifetime::collect(tracker.live_sessions(), &tracker.loadouts)
+                .into_iter()
+                .map(|lifetime| (lifetime.loadout_id.clone(), lifetime.damage_per_pec()))
+                .collect();
 
         let rows: Vec<Row> = loadouts_vec
             .iter()
@@ -990,21 +2184,33 @@ impl Section for TrackerUI {
                     Cell::from(loadout.scope.as_deref().unwrap_or("None")),
                     Cell::from(loadout.sight_one.as_deref().unwrap_or("None")),
                     Cell::from(loadout.sight_two.as_deref().unwrap_or("None")),
-                    Cell::from((loadout.decay.mul(Decimal::new(1, 2))).to_string()),
+                    Cell::from(loadout.decay.to_string()),
                     Cell::from(loadout.burn.to_string()),
+                    Cell::from(loadout.cost_per_shot().trunc_with_scale(6).to_string()),
                     Cell::from(
-                        Decimal::from(loadout.burn)
-                            .checked_div(
-                                Decimal::from(10000) + loadout.decay.mul(Decimal::new(1, 2)),
-                            )
-                            .unwrap_or(Decimal::ZERO)
-                            .trunc_with_scale(6)
-                            .to_string(),
+                        dpp_map
+                            .get(&loadout.id)
+                            .map(|dpp| dpp.to_string())
+                            .unwrap_or(String::from("-")),
                     ),
+                    Cell::from(loadout.finder.as_deref().unwrap_or("None")),
+                    Cell::from(loadout.cost_per_drop().trunc_with_scale(6).to_string()),
+                    Cell::from(loadout.healing_tool.as_deref().unwrap_or("None")),
+                    Cell::from(loadout.cost_per_heal().trunc_with_scale(6).to_string()),
+                    Cell::from(match (&loadout.armor, &loadout.plates) {
+                        (Some(armor), Some(plates)) => format!("{} / {}", armor, plates),
+                        (Some(armor), None) => armor.to_string(),
+                        (None, Some(plates)) => plates.to_string(),
+                        (None, None) => String::from("None"),
+                    }),
+                    Cell::from(loadout.armor_cost_per_hit().trunc_with_scale(6).to_string()),
                 ];
-                if idx == active_loadout_idx {
+                if Some(idx) == active_loadout_idx {
                     return Row::new(rows_vec).style(Style::default().fg(Color::Green));
                 }
+                if loadout.archived {
+                    return Row::new(rows_vec).style(Style::default().fg(Color::DarkGray));
+                }
                 Row::new(rows_vec).style(Style::default().fg(Color::White))
             })
             .collect();
@@ -1013,7 +2219,10 @@ impl Section for TrackerUI {
             .header(header)
             .block(
                 Block::default()
-                    .title("Loadouts")
+                    .title(match tracker.show_archived {
+                        true => "Loadouts (e to edit, v for analytics, archived shown)",
+                        false => "Loadouts (e to edit, v for analytics)",
+                    })
                     .borders(Borders::ALL)
                     .style(Style::default().fg(ui_color)),
             )
@@ -1024,15 +2233,143 @@ impl Section for TrackerUI {
             )
             .highlight_symbol(">> ")
             .widths(&[
-                Constraint::Percentage(11),
-                Constraint::Percentage(11),
-                Constraint::Percentage(11),
-                Constraint::Percentage(11),
-                Constraint::Percentage(11),
-                Constraint::Percentage(11),
-                Constraint::Percentage(11),
-                Constraint::Percentage(11),
-                Constraint::Percentage(11),
+                Constraint::Percentage(7),
+                Constraint::Percentage(7),
+                Constraint::Percentage(5),
+                Constraint::Percentage(5),
+                Constraint::Percentage(5),
+                Constraint::Percentage(5),
+                Constraint::Percentage(6),
+                Constraint::Percentage(6),
+                Constraint::Percentage(7),
+                Constraint::Percentage(5),
+                Constraint::Percentage(6),
+                Constraint::Percentage(7),
+                Constraint::Percentage(7),
+                Constraint::Percentage(7),
+                Constraint::Percentage(7),
+                Constraint::Percentage(7),
+            ]);
+
+        table
+    }
+
+    fn get_loadout_editor_section<'a>(
+        ui_color: Color,
+        editor_state: &'a LoadoutEditorState,
+    ) -> Table<'a> {
+        let header = Row::new(vec!["Field", "Value"])
+            .style(Style::default().fg(Color::Yellow))
+            .bottom_margin(1);
+        let draft = editor_state.draft.as_ref();
+        let selected_idx = editor_state.field_state.selected();
+        let editing = editor_state.field_input.mode == EditableTableMode::Edit;
+
+        let mut rows: Vec<Row> = LoadoutField::ALL
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| {
+                let value = match (editing && selected_idx == Some(idx), draft) {
+                    (true, _) => format!("{}_", editor_state.field_input.input),
+                    (false, Some(draft)) => field.value(draft),
+                    (false, None) => String::new(),
+                };
+                Row::new(vec![Cell::from(field.label()), Cell::from(value)])
+                    .style(Style::default().fg(Color::White))
+            })
+            .collect();
+        rows.push(
+            Row::new(vec![
+                Cell::from("Save"),
+                Cell::from("Enter to save, Esc to discard"),
+            ])
+            .style(Style::default().fg(Color::Green)),
+        );
+
+        let title = match &editor_state.error {
+            Some(error) => format!("Edit Loadout - {}", error),
+            None => String::from("Edit Loadout"),
+        };
+        let table = Table::new(rows)
+            .header(header)
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(ui_color)),
+            )
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(">> ")
+            .widths(&[Constraint::Percentage(45), Constraint::Percentage(55)]);
+
+        table
+    }
+
+    fn get_loadout_analytics_section<'a>(ui_color: Color, tracker: &'a Tracker) -> Table<'a> {
+        let headers = vec![
+            "Loadout",
+            "Sessions",
+            "Shots",
+            "Damage",
+            "DPP",
+            "Crit %",
+            "Miss %",
+            "Cost",
+            "Cost/Hour",
+            "Loot",
+            "Return %",
+        ];
+        let header = Row::new(headers)
+            .style(Style::default().fg(Color::Yellow))
+            .bottom_margin(1);
+        let rows: Vec<Row> = LoadoutLifetime::collect(tracker.live_sessions(), &tracker.loadouts)
+            .into_iter()
+            .map(|lifetime| {
+                let rows_vec = vec![
+                    Cell::from(lifetime.loadout_name.clone()),
+                    Cell::from(lifetime.session_count.to_string()),
+                    Cell::from(lifetime.attack_count.to_string()),
+                    Cell::from(lifetime.damage.trunc_with_scale(2).to_string()),
+                    Cell::from(lifetime.damage_per_pec().to_string()),
+                    Cell::from(format!("{}%", lifetime.crit_rate())),
+                    Cell::from(format!("{}%", lifetime.miss_rate())),
+                    Cell::from(lifetime.cost.trunc_with_scale(4).to_string()),
+                    Cell::from(lifetime.cost_per_hour().to_string()),
+                    Cell::from(lifetime.loot_value.trunc_with_scale(4).to_string()),
+                    Cell::from(format!("{}%", lifetime.return_percentage())),
+                ];
+                Row::new(rows_vec).style(Style::default().fg(Color::White))
+            })
+            .collect();
+
+        let title = match tracker.show_archived {
+            true => "Loadout Analytics, archived sessions included (v to go back)",
+            false => "Loadout Analytics (v to go back)",
+        };
+        let table = Table::new(rows)
+            .header(header)
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(ui_color)),
+            )
+            .widths(&[
+                Constraint::Percentage(16),
+                Constraint::Percentage(8),
+                Constraint::Percentage(8),
+                Constraint::Percentage(9),
+                Constraint::Percentage(8),
+                Constraint::Percentage(8),
+                Constraint::Percentage(8),
+                Constraint::Percentage(9),
+                Constraint::Percentage(9),
+                Constraint::Percentage(8),
+                Constraint::Percentage(8),
             ]);
 
         table
@@ -1082,4 +2419,315 @@ impl Section for TrackerUI {
 
         table
     }
+
+    // GLOBALS
+    fn get_globals_section<'a>(
+        ui_color: Color,
+        globals: Vec<&'a GlobalRecord>,
+        filter_state: &'a EditableTableState,
+        filter: &'a str,
+    ) -> Table<'a> {
+        let headers = vec!["Time", "Category", "Player", "Target", "Value", "HoF"];
+        let header = Row::new(headers)
+            .style(Style::default().fg(Color::Yellow))
+            .bottom_margin(1);
+        let rows: Vec<Row> = globals
+            .iter()
+            .map(|record| {
+                let rows_vec = vec![
+                    Cell::from(record.timestamp.format(TIMESTAMP_FORMAT).to_string()),
+                    Cell::from(record.category.name()),
+                    Cell::from(record.player.as_str()),
+                    Cell::from(record.target.as_str()),
+                    Cell::from(format!("{} PED", record.value)),
+                    Cell::from(if record.hof { "Yes" } else { "" }),
+                ];
+                let color = if record.hof {
+                    Color::Yellow
+                } else {
+                    Color::White
+                };
+                Row::new(rows_vec).style(Style::default().fg(color))
+            })
+            .collect();
+
+        let title = match filter_state.mode {
+            EditableTableMode::Edit => format!("Global History (filter: {}_)", filter_state.input),
+            EditableTableMode::View if !filter.is_empty() => {
+                format!("Global History (filter: {})", filter)
+            }
+            EditableTableMode::View => String::from("Global History (f to filter)"),
+        };
+        let table = Table::new(rows)
+            .header(header)
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(ui_color)),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+            .highlight_symbol(">> ")
+            .widths(&[
+                Constraint::Percentage(22),
+                Constraint::Percentage(10),
+                Constraint::Percentage(22),
+                Constraint::Percentage(24),
+                Constraint::Percentage(14),
+                Constraint::Percentage(8),
+            ]);
+
+        table
+    }
+
+    fn get_global_rates_section<'a>(ui_color: Color, tracker: &'a Tracker) -> Table<'a> {
+        let headers = vec!["Session", "Globals", "HoFs", "Per Kill", "Per Hour"];
+        let header = Row::new(headers)
+            .style(Style::default().fg(Color::Yellow))
+            .bottom_margin(1);
+        let mut sessions_vec: Vec<&Session> = tracker
+            .sessions
+            .values()
+            .map(
+                |session| match session.name == tracker.current_session.name {
+                    true => &tracker.current_session,
+                    false => session,
+                },
+            )
+            .collect();
+        sessions_vec.sort_by_key(|session| Reverse(session.created_at));
+        let rows: Vec<Row> = sessions_vec
+            .iter()
+            .map(|session| {
+                let global_count = Decimal::from(session.stats.global_count);
+                let hunting_count =
+                    Decimal::from(session.stats.global_tally(GlobalCategory::Hunting).count);
+                let per_hour = global_count
+                    .checked_div(Decimal::from(session.elapsed().num_seconds()))
+                    .unwrap_or(Decimal::ZERO)
+                    .mul(Decimal::from(3600))
+                    .trunc_with_scale(2);
+                let rows_vec = vec![
+                    Cell::from(session.name.as_str()),
+                    Cell::from(session.stats.global_count.to_string()),
+                    Cell::from(session.stats.hof_count.to_string()),
+                    Cell::from(format!(
+                        "{}%",
+                        Utils::get_percentage(
+                            hunting_count,
+                            Decimal::from(session.loot_pack_stats().pack_count)
+                        )
+                    )),
+                    Cell::from(per_hour.to_string()),
+                ];
+                Row::new(rows_vec).style(Style::default().fg(Color::White))
+            })
+            .collect();
+
+        let table = Table::new(rows)
+            .header(header)
+            .block(
+                Block::default()
+                    .title("Own Global Rate")
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(ui_color)),
+            )
+            .widths(&[
+                Constraint::Percentage(40),
+                Constraint::Percentage(15),
+                Constraint::Percentage(15),
+                Constraint::Percentage(15),
+                Constraint::Percentage(15),
+            ]);
+
+        table
+    }
+
+    fn get_global_categories_section<'a>(ui_color: Color, tracker: &'a Tracker) -> Paragraph<'a> {
+        let category_items: Vec<Spans> = GlobalCategory::ALL
+            .iter()
+            .map(|category| {
+                let tally = tracker.current_session.stats.global_tally(*category);
+                Spans::from(Span::raw(format!(
+                    "{}: {} ({} HoF) {} PED",
+                    category.name(),
+                    tally.count,
+                    tally.hof_count,
+                    tally.total_value
+                )))
+            })
+            .collect();
+
+        let paragraph = Paragraph::new(category_items)
+            .block(
+                Block::default()
+                    .title("Session Globals")
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(ui_color)),
+            )
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(Color::White));
+
+        paragraph
+    }
+
+    // CRAFTING
+    fn get_blueprints_section<'a>(ui_color: Color, tracker: &'a Tracker) -> Table<'a> {
+        let headers = vec![
+            "Blueprint",
+            "Clicks",
+            "Success",
+            "Near",
+            "Fail",
+            "Success %",
+            "Input Cost",
+            "Output Value",
+            "Return %",
+        ];
+        let header = Row::new(headers)
+            .style(Style::default().fg(Color::Yellow))
+            .bottom_margin(1);
+        let session = &tracker.current_session;
+        let rows: Vec<Row> = session
+            .blueprint_names()
+            .iter()
+            .map(|name| {
+                let blueprint_stats = session
+                    .stats
+                    .blueprint_map
+                    .get(*name)
+                    .cloned()
+                    .unwrap_or(BlueprintStats::new(name));
+                let input_cost = blueprint_stats.input_cost(&tracker.markups);
+                let output_value = blueprint_stats.output_value(&tracker.markups);
+                let rows_vec = vec![
+                    Cell::from(name.as_str()),
+                    Cell::from(blueprint_stats.attempt_count.to_string()),
+                    Cell::from(blueprint_stats.success_count.to_string()),
+                    Cell::from(blueprint_stats.near_success_count.to_string()),
+                    Cell::from(blueprint_stats.fail_count.to_string()),
+                    Cell::from(format!("{}%", blueprint_stats.success_rate())),
+                    Cell::from(format!("{} PED", input_cost.trunc_with_scale(4))),
+                    Cell::from(format!("{} PED", output_value.trunc_with_scale(4))),
+                    Cell::from(format!(
+                        "{}%",
+                        Utils::get_percentage(output_value, input_cost)
+                    )),
+                ];
+                if session.active_blueprint.as_ref() == Some(*name) {
+                    return Row::new(rows_vec).style(Style::default().fg(Color::Green));
+                }
+                Row::new(rows_vec).style(Style::default().fg(Color::White))
+            })
+            .collect();
+
+        let table = Table::new(rows)
+            .header(header)
+            .block(
+                Block::default()
+                    .title("Blueprints")
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(ui_color)),
+            )
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(">> ")
+            .widths(&[
+                Constraint::Percentage(20),
+                Constraint::Percentage(8),
+                Constraint::Percentage(8),
+                Constraint::Percentage(8),
+                Constraint::Percentage(8),
+                Constraint::Percentage(10),
+                Constraint::Percentage(13),
+                Constraint::Percentage(13),
+                Constraint::Percentage(10),
+            ]);
+
+        table
+    }
+
+    fn get_blueprint_details_section<'a>(
+        ui_color: Color,
+        tracker: &'a Tracker,
+        input_state: &'a EditableTableState,
+        selected_blueprint: Option<&'a String>,
+    ) -> Paragraph<'a> {
+        let session = &tracker.current_session;
+        let mut paragraph_vec = vec![Spans::from(Span::raw(format!(
+            "Activity: {}",
+            session.activity.name()
+        )))];
+        let blueprint = selected_blueprint
+            .or(session.active_blueprint.as_ref())
+            .and_then(|name| session.blueprints.get(name));
+        if let Some(blueprint) = blueprint {
+            let click_cost: Decimal = blueprint
+                .inputs
+                .iter()
+                .map(|input| {
+                    input.tt_value
+                        * tracker
+                            .markups
+                            .get(&input.name)
+                            .map(|markup| markup.value)
+                            .unwrap_or(Decimal::ONE)
+                })
+                .sum();
+            paragraph_vec.push(Spans::from(Span::raw("")));
+            paragraph_vec.push(Spans::from(Span::styled(
+                blueprint.name.as_str(),
+                Style::default().fg(Color::Yellow),
+            )));
+            for input in blueprint.inputs.iter() {
+                paragraph_vec.push(Spans::from(Span::raw(format!(
+                    "{}: {} PED",
+                    input.name, input.tt_value
+                ))));
+            }
+            paragraph_vec.push(Spans::from(Span::raw(format!(
+                "Click Cost: {} PED TT / {} PED MU",
+                blueprint.click_tt_cost(),
+                click_cost.trunc_with_scale(4)
+            ))));
+            if let Some(blueprint_stats) = session.stats.blueprint_map.get(&blueprint.name) {
+                paragraph_vec.push(Spans::from(Span::raw(format!(
+                    "Residue: {} PED",
+                    blueprint_stats.residue_value
+                ))));
+            }
+        }
+        paragraph_vec.push(Spans::from(Span::raw("")));
+        match input_state.mode {
+            EditableTableMode::Edit => {
+                paragraph_vec.push(Spans::from(Span::styled(
+                    "Blueprint (NAME; MATERIAL=TT, MATERIAL=TT):",
+                    Style::default().fg(Color::Yellow),
+                )));
+                paragraph_vec.push(Spans::from(Span::raw(input_state.input.as_str())));
+            }
+            EditableTableMode::View => {
+                paragraph_vec.push(Spans::from(Span::raw(
+                    "b to add a blueprint, right arrow to craft with the selected one",
+                )));
+            }
+        }
+
+        let paragraph = Paragraph::new(paragraph_vec)
+            .block(
+                Block::default()
+                    .title("Blueprint Details")
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(ui_color)),
+            )
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(Color::White));
+
+        paragraph
+    }
 }
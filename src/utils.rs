@@ -1,7 +1,8 @@
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 
 pub trait Helpers {
     fn get_percentage(value: Decimal, total: Decimal) -> Decimal;
+    fn get_damage_per_pec(damage: Decimal, cost: Decimal) -> Decimal;
 }
 
 pub struct Utils {}
@@ -15,4 +16,12 @@ impl Helpers for Utils {
         return (value.checked_div(total).unwrap_or(Decimal::ZERO) * Decimal::ONE_HUNDRED)
             .trunc_with_scale(2);
     }
+
+    // Quotients at full precision lose digits in `trunc_with_scale`
+    fn get_damage_per_pec(damage: Decimal, cost: Decimal) -> Decimal {
+        damage
+            .checked_div(cost * Decimal::ONE_HUNDRED)
+            .unwrap_or(Decimal::ZERO)
+            .round_dp_with_strategy(4, RoundingStrategy::ToZero)
+    }
 }